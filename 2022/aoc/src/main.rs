@@ -0,0 +1,45 @@
+//! Runner for the tools of the days that have more than their two answers
+
+use no_space_left_on_device::{explore::Session, FileSystem};
+
+const USAGE: &str = "usage: aoc explore <year> <day>";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["explore", year, day] => explore(year, day),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Interactive session over the puzzle input of `year`/`day`
+fn explore(year: &str, day: &str) {
+    match (year.parse(), day.parse()) {
+        (Ok(2022), Ok(7)) => {
+            let input = &aoc_common::puzzle_input!("../../day_7/input.txt");
+            let fs = match FileSystem::from_transcript(input) {
+                Ok(fs) => fs,
+                Err(e) => {
+                    eprintln!("{e}");
+                    std::process::exit(1);
+                }
+            };
+            let stdin = std::io::stdin().lock();
+            if let Err(e) = Session::new(&fs).repl(stdin, std::io::stdout()) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        (Ok::<u16, _>(_), Ok::<u8, _>(_)) => {
+            eprintln!("no explorer for {year} day {day}");
+            std::process::exit(1);
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
+}
@@ -1,19 +1,56 @@
-/// An iterator that takes `Option<u64>` items and yields sums of groups of
-/// `Some(u64)` items separated by `None` items.
-pub struct GroupSumIter<I> {
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    iter::{Enumerate, Map},
+    marker::PhantomData,
+    str::Lines,
+};
+
+use aoc_common::prelude::*;
+
+/// An iterator that takes `Option<T>` items and folds each group of `Some(T)`
+/// items separated by `None` items into one value.
+///
+/// [`GroupSumIter::new`] sums the groups, [`GroupSumIter::with_fold`] takes
+/// any other aggregation: `fold` gets `None` for the first item of a group and
+/// the value so far for the next ones.
+pub struct GroupSumIter<I, F, G = u128> {
     pub inner: I,
+    fold: F,
+    group: PhantomData<fn() -> G>,
 }
 
-impl<I> Iterator for GroupSumIter<I>
+impl<I> GroupSumIter<I, fn(Option<u128>, u64) -> u128>
 where
     I: Iterator<Item = Option<u64>>,
 {
-    type Item = u64;
+    /// Sums of the groups, wide enough not to overflow
+    pub fn new(inner: I) -> Self {
+        Self::with_fold(inner, |sum, v| sum.unwrap_or(0) + u128::from(v))
+    }
+}
+
+impl<I, F, G> GroupSumIter<I, F, G> {
+    pub fn with_fold(inner: I, fold: F) -> Self {
+        Self {
+            inner,
+            fold,
+            group: PhantomData,
+        }
+    }
+}
+
+impl<I, T, G, F> Iterator for GroupSumIter<I, F, G>
+where
+    I: Iterator<Item = Option<T>>,
+    F: FnMut(Option<G>, T) -> G,
+{
+    type Item = G;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut sum = loop {
+        let mut group = loop {
             match self.inner.next() {
-                Some(Some(v)) => break v,
+                Some(Some(v)) => break (self.fold)(None, v),
                 Some(None) => {
                     // huh, weird, didn't expect a separator there
                     // but let's just skip it
@@ -25,12 +62,307 @@ where
 
         loop {
             match self.inner.next() {
-                Some(Some(v)) => sum += v,
+                Some(Some(v)) => group = (self.fold)(Some(group), v),
                 Some(None) | None => {
                     // reached a separator or the end of the iterator
-                    break Some(sum);
+                    break Some(group);
                 }
             }
         }
     }
 }
+
+/// Numbers carried by one elf, a group of lines between blank lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    /// Elf number, the first group being elf 1
+    pub elf: usize,
+    /// Input line the group starts on, 1-based
+    pub line: usize,
+    pub items: Vec<u64>,
+}
+
+impl Group {
+    pub fn total(&self) -> u128 {
+        self.items.iter().copied().map(u128::from).sum()
+    }
+
+    /// # Panics
+    ///
+    /// If the group has no items, which never happens for groups coming out
+    /// of [`groups`].
+    pub fn stats(&self) -> GroupStats {
+        let mut sorted = self.items.clone();
+        sorted.sort_unstable();
+        let count = sorted.len();
+        let total = self.total();
+        let median = match count % 2 {
+            0 => (u128::from(sorted[count / 2 - 1]) + u128::from(sorted[count / 2])) as f64 / 2.0,
+            _ => sorted[count / 2] as f64,
+        };
+
+        GroupStats {
+            elf: self.elf,
+            count,
+            total,
+            min: sorted[0],
+            max: sorted[count - 1],
+            mean: total as f64 / count as f64,
+            median,
+        }
+    }
+}
+
+/// Summary of a [`Group`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupStats {
+    pub elf: usize,
+    pub count: usize,
+    pub total: u128,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub median: f64,
+}
+
+/// Total carried by an elf, as returned by [`top_k`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ElfTotal {
+    pub total: u128,
+    /// Reversed so that on equal totals the first elf wins
+    elf: Reverse<usize>,
+}
+
+impl ElfTotal {
+    pub fn new(elf: usize, total: u128) -> Self {
+        Self {
+            total,
+            elf: Reverse(elf),
+        }
+    }
+
+    pub fn elf(&self) -> usize {
+        self.elf.0
+    }
+}
+
+/// Item of a line, with its 1-based line number, none for a blank line
+type LineItem = Option<Result<(usize, u64), ParseError>>;
+
+type LineItems<'a> = Map<Enumerate<Lines<'a>>, fn((usize, &str)) -> LineItem>;
+
+type GroupFold = fn(
+    Option<Result<Group, ParseError>>,
+    Result<(usize, u64), ParseError>,
+) -> Result<Group, ParseError>;
+
+/// Streaming parser of blank-line separated groups of numbers, a
+/// [`GroupSumIter`] collecting the items of each group.
+///
+/// Runs of blank lines (including lines with only whitespace) count as a
+/// single separator, and `\r\n` line endings are accepted. A line that is not
+/// a number stops the iteration with an error pointing at it.
+pub struct Groups<'a> {
+    groups: GroupSumIter<LineItems<'a>, GroupFold, Result<Group, ParseError>>,
+    elf: usize,
+    failed: bool,
+}
+
+impl<'a> Groups<'a> {
+    pub fn new(input: &'a str) -> Self {
+        let items: LineItems<'a> = input.lines().enumerate().map(parse_line);
+        Self {
+            groups: GroupSumIter::with_fold(items, add_item),
+            elf: 0,
+            failed: false,
+        }
+    }
+}
+
+fn parse_line((idx, line): (usize, &str)) -> LineItem {
+    let value = line.trim();
+    if value.is_empty() {
+        return None;
+    }
+    let item = value.parse::<u64>().map_err(|_| {
+        let column = line.len() - line.trim_start().len() + 1;
+        let kind = ParseErrorKind::InvalidNumber(value.to_owned());
+        ParseError::new(idx + 1, column, kind)
+    });
+    Some(item.map(|item| (idx + 1, item)))
+}
+
+/// Adds an item to its group, the first error of a group sticking
+fn add_item(
+    group: Option<Result<Group, ParseError>>,
+    item: Result<(usize, u64), ParseError>,
+) -> Result<Group, ParseError> {
+    let group = group.transpose()?;
+    let (line, item) = item?;
+    let mut group = group.unwrap_or(Group {
+        elf: 0,
+        line,
+        items: Vec::new(),
+    });
+    group.items.push(item);
+    Ok(group)
+}
+
+impl Iterator for Groups<'_> {
+    type Item = Result<Group, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let group = self.groups.next()?;
+        self.failed = group.is_err();
+        Some(group.map(|group| {
+            self.elf += 1;
+            Group {
+                elf: self.elf,
+                ..group
+            }
+        }))
+    }
+}
+
+pub fn groups(input: &str) -> Groups<'_> {
+    Groups::new(input)
+}
+
+/// The `k` largest group totals, largest first.
+///
+/// Only `k` totals are kept in memory at any time, whatever the number of
+/// groups.
+pub fn top_k<I>(groups: I, k: usize) -> Result<Vec<ElfTotal>, ParseError>
+where
+    I: IntoIterator<Item = Result<Group, ParseError>>,
+{
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for group in groups {
+        let group = group?;
+        heap.push(Reverse(ElfTotal::new(group.elf, group.total())));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+
+    let mut top = heap
+        .into_iter()
+        .map(|Reverse(total)| total)
+        .collect::<Vec<_>>();
+    top.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(top)
+}
+
+/// Stats of every group, in input order
+pub fn group_stats(input: &str) -> Result<Vec<GroupStats>, ParseError> {
+    groups(input)
+        .map(|group| group.map(|g| g.stats()))
+        .collect()
+}
+
+/// Calories carried by the elf carrying the most
+pub fn part1(input: &str) -> Result<u128, ParseError> {
+    Ok(top_k(groups(input), 1)?.first().map_or(0, |top| top.total))
+}
+
+/// Calories carried by the three elves carrying the most
+pub fn part2(input: &str) -> Result<u128, ParseError> {
+    Ok(top_k(groups(input), 3)?.iter().map(|top| top.total).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_macros::examples! {
+        part1: |input| part1(input).unwrap(),
+        part2: |input| part2(input).unwrap(),
+    }
+
+    #[test]
+    fn top_k_keeps_elves() {
+        let input = include_str!("../examples/sample.txt");
+        let top = top_k(groups(input), 2).unwrap();
+        assert_eq!(top, [ElfTotal::new(4, 24000), ElfTotal::new(3, 11000)]);
+        assert_eq!(top[0].elf(), 4);
+        assert_eq!(top_k(groups(input), 10).unwrap().len(), 5);
+        assert!(top_k(groups(input), 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn equal_totals_prefer_first_elf() {
+        let top = top_k(groups("5\n\n2\n3\n\n1"), 1).unwrap();
+        assert_eq!(top, [ElfTotal::new(1, 5)]);
+    }
+
+    #[test]
+    fn blank_runs_and_crlf() {
+        let input = "\r\n1\r\n2\r\n\r\n\r\n  \r\n3\r\n\r\n";
+        let groups = groups(input).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            groups,
+            [
+                Group {
+                    elf: 1,
+                    line: 2,
+                    items: vec![1, 2]
+                },
+                Group {
+                    elf: 2,
+                    line: 7,
+                    items: vec![3]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn error_has_line_number() {
+        let error = part1("1000\n2000\n\n40x0\n").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(error.kind, ParseErrorKind::InvalidNumber("40x0".to_owned()));
+    }
+
+    #[test]
+    fn group_folds() {
+        let items = [None, Some(1), Some(2), None, None, Some(3)];
+        let sums = GroupSumIter::new(items.into_iter()).collect::<Vec<_>>();
+        assert_eq!(sums, [3, 3]);
+        let counts = GroupSumIter::with_fold(items.into_iter(), |count: Option<usize>, _| {
+            count.unwrap_or(0) + 1
+        });
+        assert_eq!(counts.collect::<Vec<_>>(), [2, 1]);
+    }
+
+    #[test]
+    fn large_totals_dont_overflow() {
+        let input = format!("{max}\n{max}\n\n1", max = u64::MAX);
+        let stats = group_stats(&input).unwrap();
+        assert_eq!(stats[0].total, 2 * u128::from(u64::MAX));
+        assert_eq!(stats[0].median, u64::MAX as f64);
+        assert_eq!(part1(&input).unwrap(), 2 * u128::from(u64::MAX));
+    }
+
+    #[test]
+    fn stats() {
+        let stats = group_stats("1000\n2000\n3000\n\n4000\n\n6000\n5000").unwrap();
+        assert_eq!(
+            stats[0],
+            GroupStats {
+                elf: 1,
+                count: 3,
+                total: 6000,
+                min: 1000,
+                max: 3000,
+                mean: 2000.0,
+                median: 2000.0,
+            }
+        );
+        assert_eq!(stats[1].median, 4000.0);
+        assert_eq!(stats[2].median, 5500.0);
+        assert_eq!(stats[2].elf, 3);
+    }
+}
@@ -1,31 +1,37 @@
-use calorie_counting::GroupSumIter;
+use aoc_common::puzzle_input;
+use calorie_counting::{group_stats, groups, part1, part2, top_k, GroupSumIter};
 use itertools::Itertools;
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
 
-    let input = include_str!("../input_test.txt");
+    let input = &puzzle_input!("../examples/sample.txt");
 
-    let sum = calculate_max_calories_from_str(input);
     println!("-----------------------------------------");
-    println!("{}", sum);
+    println!("{}", part1(input)?);
     println!("-----------------------------------------");
-    println!("Top 3: {:?}", calculate_top_three(input));
+    println!("Top 3: {:?}", part2(input)?);
 
-    let input = include_str!("../input.txt");
+    let input = &puzzle_input!("../input.txt");
 
-    let sum = calculate_max_calories_from_str(input);
     println!("-----------------------------------------");
-    println!("{}", sum);
+    println!("{}", part1(input)?);
     println!("-----------------------------------------");
-    println!("Top 3: {:?}", calculate_top_three(input));
+    println!("Top 3: {:?}", part2(input)?);
+    for top in top_k(groups(input), 3)? {
+        println!("Elf {}: {}", top.elf(), top.total);
+    }
+    let stats = group_stats(input)?;
+    if let Some(biggest) = stats.iter().max_by_key(|s| s.count) {
+        println!("Most items: {biggest:?}");
+    }
 
     println!("-----------------------------------------");
     println!("----------FasterThanLiMe way-------------");
     println!("-----------------------------------------");
     // FasterThanLiMe way
     {
-        let lines = include_str!("../input.txt")
+        let lines = input
             .lines()
             .map(|v| v.parse::<u64>().ok())
             .collect::<Vec<_>>();
@@ -44,16 +50,14 @@ fn main() -> color_eyre::Result<()> {
     }
     // Selfwritten iterator way
     {
-        let lines = include_str!("../input.txt")
-            .lines()
-            .map(|v| v.parse::<u64>().ok());
+        let lines = input.lines().map(|v| v.parse::<u64>().ok());
         // here! 👋
-        let elven_lead = GroupSumIter { inner: lines }.max();
+        let elven_lead = GroupSumIter::new(lines).max();
         println!("{elven_lead:?}");
     }
     // Itertools batching way
     {
-        let max = include_str!("../input.txt")
+        let max = input
             .lines()
             .map(|v| v.parse::<u64>().ok())
             .batching(|it| {
@@ -68,7 +72,7 @@ fn main() -> color_eyre::Result<()> {
     }
     // Itertools coalesce way
     {
-        let max = include_str!("../input.txt")
+        let max = input
             .lines()
             .map(|v| v.parse::<u64>().ok())
             .coalesce(|a, b| match (a, b) {
@@ -85,51 +89,3 @@ fn main() -> color_eyre::Result<()> {
 
     Ok(())
 }
-
-fn calculate_max_calories_from_str(input: &str) -> u32 {
-    let splitter = match input.find('\r') {
-        Some(_) => "\r\n\r\n",
-        None => "\n\n",
-    };
-
-    // dbg!(splitter);
-    let sum = input
-        .split(splitter)
-        .map(|group| {
-            // dbg!(group);
-            group
-                .lines()
-                .map(|value| {
-                    // dbg!(value);
-                    value.parse::<u32>().unwrap()
-                })
-                .sum::<u32>()
-        })
-        .max();
-
-    sum.unwrap_or(0)
-}
-
-fn calculate_top_three(input: &str) -> u32 {
-    let splitter = match input.find('\r') {
-        Some(_) => "\r\n\r\n",
-        None => "\n\n",
-    };
-
-    // dbg!(splitter);
-    let mut sum: Vec<_> = input
-        .split(splitter)
-        .map(|group| {
-            // dbg!(group);
-            group
-                .lines()
-                .map(|value| {
-                    // dbg!(value);
-                    value.parse::<u32>().unwrap()
-                })
-                .sum::<u32>()
-        })
-        .collect();
-    sum.sort_by(|a, b| b.cmp(a));
-    sum.iter().take(3).sum()
-}
@@ -1,108 +1,550 @@
-use std::str::FromStr;
+//! CPU crate
+//! # Purpose
+//! A clock circuit CPU whose instructions come from a table,
+//! with hooks called during every cycle, breakpoints and traces
 
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    combinator::{map, value},
-    error::Error,
-    sequence::separated_pair,
-    Finish, IResult,
-};
+use std::fmt;
 
+use aoc_common::prelude::{ParseError, ParseErrorKind};
+
+/// Content of a register, and of instruction operands
+pub type Value = i64;
+
+/// What an instruction does to the registers at the end of its last cycle
+pub type Effect = fn(&mut Registers, &[Value]);
+
+/// One entry of an [`InstructionSet`]
 #[derive(Debug, Clone, Copy)]
-pub enum Instruction {
-    Noop,
-    Addx(i32),
+pub struct Opcode {
+    pub name: &'static str,
+    /// Cycles the instruction takes, at least 1
+    pub cycles: u32,
+    /// Number of integer operands following the name
+    pub operands: usize,
+    pub effect: Effect,
+}
+
+/// Named registers of a [`Cpu`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registers {
+    names: Vec<&'static str>,
+    values: Vec<Value>,
+}
+
+impl Registers {
+    fn index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|&n| n == name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.index(name).map(|idx| self.values[idx])
+    }
+
+    /// # Panics
+    ///
+    /// If there is no register `name`, instruction sets only use the
+    /// registers they declare.
+    pub fn set(&mut self, name: &str, value: Value) {
+        let idx = self
+            .index(name)
+            .unwrap_or_else(|| panic!("no register `{name}`"));
+        self.values[idx] = value;
+    }
+
+    /// The `X` register of the puzzle
+    pub fn x(&self) -> Value {
+        self.get("X").unwrap_or_default()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, Value)> + '_ {
+        self.names.iter().copied().zip(self.values.iter().copied())
+    }
+}
+
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (name, value)) in self.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{name}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Registers and opcodes a [`Cpu`] knows about
+#[derive(Debug, Clone)]
+pub struct InstructionSet {
+    registers: Registers,
+    opcodes: Vec<Opcode>,
+}
+
+impl Default for InstructionSet {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl InstructionSet {
+    /// No register and no opcode
+    pub fn empty() -> Self {
+        Self {
+            registers: Registers {
+                names: Vec::new(),
+                values: Vec::new(),
+            },
+            opcodes: Vec::new(),
+        }
+    }
+
+    /// The puzzle CPU: `X` starting at 1, `noop` and `addx V`
+    pub fn standard() -> Self {
+        Self::empty()
+            .with_register("X", 1)
+            .with_opcode(Opcode {
+                name: "noop",
+                cycles: 1,
+                operands: 0,
+                effect: |_, _| {},
+            })
+            .with_opcode(Opcode {
+                name: "addx",
+                cycles: 2,
+                operands: 1,
+                effect: |registers, operands| registers.set("X", registers.x() + operands[0]),
+            })
+    }
+
+    /// Adds register `name`, or changes its initial value if it exists
+    pub fn with_register(mut self, name: &'static str, initial: Value) -> Self {
+        match self.registers.index(name) {
+            Some(idx) => self.registers.values[idx] = initial,
+            None => {
+                self.registers.names.push(name);
+                self.registers.values.push(initial);
+            }
+        }
+        self
+    }
+
+    /// Adds `opcode`, replacing the one with the same name if there is one
+    ///
+    /// # Panics
+    ///
+    /// If the opcode takes no cycle, its effect would never be seen by hooks.
+    pub fn with_opcode(mut self, opcode: Opcode) -> Self {
+        assert!(opcode.cycles > 0, "opcode `{}` takes no cycle", opcode.name);
+        match self.opcodes.iter_mut().find(|op| op.name == opcode.name) {
+            Some(existing) => *existing = opcode,
+            None => self.opcodes.push(opcode),
+        }
+        self
+    }
+
+    pub fn opcode(&self, name: &str) -> Option<&Opcode> {
+        self.opcodes.iter().find(|op| op.name == name)
+    }
+
+    pub fn opcodes(&self) -> &[Opcode] {
+        &self.opcodes
+    }
+
+    /// Registers with their initial values
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    fn parse_line(&self, line: &str) -> Result<Instruction, ParseError> {
+        let mut tokens = line.split_whitespace();
+        let Some(name) = tokens.next() else {
+            return Err(ParseError::at_offset(
+                line,
+                0,
+                ParseErrorKind::Expected("an instruction".to_owned()),
+            ));
+        };
+        let opcode = *self.opcode(name).ok_or_else(|| {
+            ParseError::at(
+                line,
+                name,
+                ParseErrorKind::Invalid(format!("unknown instruction `{name}`")),
+            )
+        })?;
+        let operands = tokens
+            .map(|token| {
+                token.parse().map_err(|_| {
+                    ParseError::at(line, token, ParseErrorKind::InvalidNumber(token.to_owned()))
+                })
+            })
+            .collect::<Result<Vec<Value>, _>>()?;
+        if operands.len() != opcode.operands {
+            return Err(ParseError::at_offset(
+                line,
+                line.len(),
+                ParseErrorKind::Expected(format!(
+                    "{} operand(s) for `{name}`, found {}",
+                    opcode.operands,
+                    operands.len()
+                )),
+            ));
+        }
+        Ok(Instruction { opcode, operands })
+    }
+
+    /// Reads a program, one instruction per line
+    pub fn parse(&self, text: &str) -> Result<Vec<Instruction>, ParseError> {
+        aoc_common::prelude::lines_with(text, |line| self.parse_line(line))
+    }
+}
+
+/// An opcode with its operands
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    opcode: Opcode,
+    operands: Vec<Value>,
 }
 
 impl Instruction {
-    pub fn cycles(&self) -> u32 {
-        match self {
-            Instruction::Noop => 1,
-            Instruction::Addx(_) => 2,
+    pub fn opcode(&self) -> &Opcode {
+        &self.opcode
+    }
+
+    pub fn operands(&self) -> &[Value] {
+        &self.operands
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.opcode.name)?;
+        for operand in &self.operands {
+            write!(f, " {operand}")?;
         }
+        Ok(())
+    }
+}
+
+/// State of the CPU during a cycle, what hooks and breakpoints look at
+#[derive(Debug, Clone, Copy)]
+pub struct Cycle<'a> {
+    /// 1 for the first cycle
+    pub number: u32,
+    pub instruction: &'a Instruction,
+    /// Values during the cycle, before the instruction ends
+    pub registers: &'a Registers,
+}
+
+/// Whether a run goes on after a hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    Stop,
+}
+
+/// Hook called during every cycle of a run
+pub trait Observer {
+    fn cycle(&mut self, cycle: &Cycle) -> Control;
+}
+
+impl<F> Observer for F
+where
+    F: FnMut(&Cycle) -> Control,
+{
+    fn cycle(&mut self, cycle: &Cycle) -> Control {
+        self(cycle)
     }
 }
 
-impl FromStr for Instruction {
-    type Err = Error<String>;
+/// Stops a run before the cycle it matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Before cycle `n`
+    Cycle(u32),
+    /// Before the first cycle where the register holds the value after
+    /// holding another one, or the first cycle of the run
+    Register { name: &'static str, value: Value },
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match parse_command(s).finish() {
-            Ok((_remaining, command)) => Ok(command),
-            Err(Error { input, code }) => Err(Error {
-                input: input.to_string(),
-                code,
-            }),
+impl Breakpoint {
+    fn hit(&self, cycle: &Cycle, previous: Option<&Registers>) -> bool {
+        match *self {
+            Breakpoint::Cycle(number) => cycle.number == number,
+            Breakpoint::Register { name, value } => {
+                cycle.registers.get(name) == Some(value)
+                    && previous.is_none_or(|previous| previous.get(name) != Some(value))
+            }
         }
     }
 }
 
-fn parse_command(input: &str) -> IResult<&str, Instruction> {
-    // prepare separate parsers as variables closures
-    let noop_parser = value(Instruction::Noop, tag("noop"));
-    let addx_parser = map(
-        separated_pair(tag("addx"), tag(" "), nom::character::complete::i32),
-        |(_addx, steps)| Instruction::Addx(steps),
-    );
-    // parse
-    alt((noop_parser, addx_parser))(input)
+/// Why a run ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Halt {
+    /// The last instruction is done
+    Finished,
+    /// Before a cycle, running again goes on from there
+    Breakpoint(Breakpoint),
+    /// After the cycle an observer asked to stop at
+    Stopped,
 }
 
+#[derive(Debug, Clone)]
 pub struct Cpu {
+    program: Vec<Instruction>,
+    registers: Registers,
+    /// Cycles done
     cycle: u32,
-    reg_x: i32,
-    current_command: Option<Instruction>,
-    ticks_used: u32,
+    /// Index of the running instruction
+    pc: usize,
+    /// Cycles done by the running instruction
+    elapsed: u32,
+    breakpoints: Vec<Breakpoint>,
+    /// Registers during the previous cycle
+    previous: Option<Registers>,
+    /// Set when stopped on a breakpoint, so running again doesn't stop there
+    resuming: bool,
 }
 
-impl Default for Cpu {
-    fn default() -> Self {
+impl Cpu {
+    pub fn new(set: &InstructionSet, program: Vec<Instruction>) -> Self {
         Self {
-            cycle: Default::default(),
-            reg_x: 1,
-            current_command: Default::default(),
-            ticks_used: Default::default(),
+            program,
+            registers: set.registers().clone(),
+            cycle: 0,
+            pc: 0,
+            elapsed: 0,
+            breakpoints: Vec::new(),
+            previous: None,
+            resuming: false,
         }
     }
-}
 
-impl Cpu {
-    pub fn tick(&mut self) {
-        self.cycle += 1;
-        if let Some(command) = self.current_command {
-            self.ticks_used += 1;
-            if self.ticks_used == command.cycles() {
-                match command {
-                    Instruction::Noop => (),
-                    Instruction::Addx(x) => self.reg_x += x,
+    /// CPU loaded with the program `text`
+    pub fn from_source(set: &InstructionSet, text: &str) -> Result<Self, ParseError> {
+        Ok(Self::new(set, set.parse(text)?))
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        self.breakpoints.push(breakpoint);
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Cycles done so far
+    pub fn cycle(&self) -> u32 {
+        self.cycle
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    /// Instruction of the next cycle, none once the program is done
+    pub fn current(&self) -> Option<&Instruction> {
+        self.program.get(self.pc)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    /// Runs cycles until the program ends, a breakpoint is reached or an
+    /// observer stops it. Every observer sees every cycle, in order.
+    pub fn run(&mut self, observers: &mut [&mut dyn Observer]) -> Halt {
+        while let Some(instruction) = self.program.get(self.pc) {
+            let cycle = Cycle {
+                number: self.cycle + 1,
+                instruction,
+                registers: &self.registers,
+            };
+            if !self.resuming {
+                let previous = self.previous.as_ref();
+                if let Some(breakpoint) = self.breakpoints.iter().find(|b| b.hit(&cycle, previous))
+                {
+                    self.resuming = true;
+                    return Halt::Breakpoint(breakpoint.clone());
+                }
+            }
+            self.resuming = false;
+
+            let mut control = Control::Continue;
+            for observer in observers.iter_mut() {
+                if observer.cycle(&cycle) == Control::Stop {
+                    control = Control::Stop;
                 }
-                self.set_command(None);
+            }
+            self.tick();
+            if control == Control::Stop {
+                return Halt::Stopped;
             }
         }
+        Halt::Finished
     }
 
-    pub fn set_command(&mut self, command: Option<Instruction>) {
-        self.ticks_used = 0;
-        match command {
-            Some(command) => self.current_command = Some(command),
-            None => self.current_command = None,
+    fn tick(&mut self) {
+        self.previous = Some(self.registers.clone());
+        self.cycle += 1;
+        self.elapsed += 1;
+        let instruction = &self.program[self.pc];
+        if self.elapsed == instruction.opcode.cycles {
+            (instruction.opcode.effect)(&mut self.registers, &instruction.operands);
+            self.pc += 1;
+            self.elapsed = 0;
         }
     }
+}
 
-    pub fn reg_x(&self) -> i32 {
-        self.reg_x
+/// One line of a [`Trace`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceEntry {
+    pub cycle: u32,
+    pub instruction: String,
+    pub registers: Registers,
+}
+
+/// Observer recording every cycle it sees
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    entries: Vec<TraceEntry>,
+}
+
+impl Trace {
+    pub fn entries(&self) -> &[TraceEntry] {
+        &self.entries
     }
+}
 
-    pub fn cycle(&self) -> u32 {
-        self.cycle
+impl Observer for Trace {
+    fn cycle(&mut self, cycle: &Cycle) -> Control {
+        self.entries.push(TraceEntry {
+            cycle: cycle.number,
+            instruction: cycle.instruction.to_string(),
+            registers: cycle.registers.clone(),
+        });
+        Control::Continue
+    }
+}
+
+impl fmt::Display for Trace {
+    /// One line per cycle: its number, the running instruction and the
+    /// registers during the cycle
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{:>4}  {:<12}{}",
+                entry.cycle, entry.instruction, entry.registers
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SMALL: &str = "noop\naddx 3\naddx -5";
+
+    fn x_values(cpu: &mut Cpu) -> Vec<Value> {
+        let mut values = Vec::new();
+        let mut hook = |cycle: &Cycle| {
+            values.push(cycle.registers.x());
+            Control::Continue
+        };
+        cpu.run(&mut [&mut hook]);
+        values
+    }
+
+    #[test]
+    fn small_program() {
+        let mut cpu = Cpu::from_source(&InstructionSet::standard(), SMALL).unwrap();
+        assert_eq!(x_values(&mut cpu), [1, 1, 1, 4, 4]);
+        assert_eq!(cpu.registers().x(), -1);
+        assert_eq!(cpu.cycle(), 5);
+        assert!(cpu.is_finished());
+    }
+
+    #[test]
+    fn new_registers_and_opcodes() {
+        let set = InstructionSet::standard()
+            .with_register("Y", 0)
+            .with_opcode(Opcode {
+                name: "muly",
+                cycles: 3,
+                operands: 2,
+                effect: |registers, operands| {
+                    registers.set("Y", registers.x() * operands[0] + operands[1])
+                },
+            });
+        let mut cpu = Cpu::from_source(&set, "addx 4\nmuly 2 -1\nnoop").unwrap();
+        let mut trace = Trace::default();
+        assert_eq!(cpu.run(&mut [&mut trace]), Halt::Finished);
+        assert_eq!(cpu.registers().get("Y"), Some(9));
+        assert_eq!(trace.entries().len(), 6);
+        assert_eq!(
+            trace.to_string().lines().nth(4),
+            Some("   5  muly 2 -1   X=5 Y=0")
+        );
+        assert_eq!(
+            trace.to_string().lines().last(),
+            Some("   6  noop        X=5 Y=9")
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let set = InstructionSet::standard();
+        let error = set.parse("noop\nadd 3").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        let error = set.parse("addx 3 4").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 9));
+        let error = set.parse("noop\naddx x").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new(2, 6, ParseErrorKind::InvalidNumber("x".to_owned()))
+        );
     }
 
-    pub fn signal_strength(&self) -> i32 {
-        self.cycle() as i32 * self.reg_x()
+    #[test]
+    fn breakpoints() {
+        let set = InstructionSet::standard();
+        let mut cpu = Cpu::from_source(&set, SMALL).unwrap();
+        cpu.add_breakpoint(Breakpoint::Cycle(3));
+        cpu.add_breakpoint(Breakpoint::Register {
+            name: "X",
+            value: 4,
+        });
+        assert_eq!(cpu.run(&mut []), Halt::Breakpoint(Breakpoint::Cycle(3)));
+        assert_eq!(cpu.cycle(), 2);
+        assert_eq!(
+            cpu.run(&mut []),
+            Halt::Breakpoint(Breakpoint::Register {
+                name: "X",
+                value: 4
+            })
+        );
+        assert_eq!(cpu.cycle(), 3);
+        assert_eq!(x_values(&mut cpu), [4, 4]);
     }
 
-    pub fn current_command(&self) -> Option<Instruction> {
-        self.current_command
+    #[test]
+    fn observers_stop_runs() {
+        let mut cpu = Cpu::from_source(&InstructionSet::standard(), SMALL).unwrap();
+        let mut stop_at_2 = |cycle: &Cycle| match cycle.number {
+            2 => Control::Stop,
+            _ => Control::Continue,
+        };
+        let mut trace = Trace::default();
+        assert_eq!(cpu.run(&mut [&mut stop_at_2, &mut trace]), Halt::Stopped);
+        assert_eq!(cpu.cycle(), 2);
+        assert_eq!(trace.entries().len(), 2);
+        assert_eq!(
+            cpu.current().map(ToString::to_string).as_deref(),
+            Some("addx 3")
+        );
     }
 }
@@ -0,0 +1,225 @@
+//! CRT crate
+//! # Purpose
+//! Screen drawn one pixel per CPU cycle,
+//! lit where the sprite centred on `X` covers the pixel
+
+use std::fmt;
+
+use crate::cpu::{Control, Cycle, Observer, Value};
+
+/// Screen filled row by row, one pixel per cycle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crt {
+    width: usize,
+    height: usize,
+    sprite_width: usize,
+    lit: char,
+    dark: char,
+    pixels: Vec<bool>,
+    /// Pixels drawn so far
+    drawn: usize,
+}
+
+impl Default for Crt {
+    /// The puzzle screen: 40x6 pixels, a 3 pixel sprite, `#` and `.`
+    fn default() -> Self {
+        Self::new(40, 6)
+    }
+}
+
+impl Crt {
+    /// Screen of `width` by `height` pixels with a 3 pixel sprite
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            sprite_width: 3,
+            lit: '#',
+            dark: '.',
+            pixels: vec![false; width * height],
+            drawn: 0,
+        }
+    }
+
+    /// Sprite covering `sprite_width` pixels from `X - (sprite_width - 1) / 2`,
+    /// so an even width has one more pixel right of `X`
+    pub fn with_sprite_width(mut self, sprite_width: usize) -> Self {
+        self.sprite_width = sprite_width;
+        self
+    }
+
+    /// Characters of the lit and dark pixels in the rendered lines
+    pub fn with_glyphs(mut self, lit: char, dark: char) -> Self {
+        self.lit = lit;
+        self.dark = dark;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Pixels drawn so far
+    pub fn drawn(&self) -> usize {
+        self.drawn
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.drawn == self.pixels.len()
+    }
+
+    pub fn is_lit(&self, row: usize, col: usize) -> bool {
+        row < self.height && col < self.width && self.pixels[row * self.width + col]
+    }
+
+    /// Whether the sprite at `x` covers column `col`
+    fn covers(&self, x: Value, col: usize) -> bool {
+        let start = x - (self.sprite_width as Value - 1).div_euclid(2);
+        (start..start + self.sprite_width as Value).contains(&(col as Value))
+    }
+
+    /// Draws the next pixel with the sprite at `x`, false once the screen is
+    /// full
+    pub fn draw(&mut self, x: Value) -> bool {
+        if self.is_complete() {
+            return false;
+        }
+        let col = self.drawn % self.width;
+        self.pixels[self.drawn] = self.covers(x, col);
+        self.drawn += 1;
+        true
+    }
+
+    /// Draws the pixels left once the program ended, `X` keeping its last
+    /// value `x`
+    pub fn finish(&mut self, x: Value) {
+        while self.draw(x) {}
+    }
+
+    /// One string per row
+    pub fn lines(&self) -> Vec<String> {
+        self.pixels
+            .chunks(self.width.max(1))
+            .map(|row| {
+                row.iter()
+                    .map(|&lit| if lit { self.lit } else { self.dark })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// One `Vec` of lit pixels per row
+    pub fn grid(&self) -> Vec<Vec<bool>> {
+        self.pixels
+            .chunks(self.width.max(1))
+            .map(<[bool]>::to_vec)
+            .collect()
+    }
+
+    /// Every row as a number whose bits are its pixels, the leftmost one the
+    /// most significant, none if the screen is wider than 64 pixels
+    pub fn packed_rows(&self) -> Option<Vec<u64>> {
+        if self.width > 64 {
+            return None;
+        }
+        let rows = self
+            .pixels
+            .chunks(self.width.max(1))
+            .map(|row| row.iter().fold(0, |bits, &lit| bits << 1 | u64::from(lit)))
+            .collect();
+        Some(rows)
+    }
+}
+
+impl fmt::Display for Crt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for line in self.lines() {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Observer for Crt {
+    /// Draws one pixel per cycle, ignoring the cycles past a full screen so
+    /// that the other observers of the run see the whole program
+    fn cycle(&mut self, cycle: &Cycle) -> Control {
+        self.draw(cycle.registers.x());
+        Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::{Cpu, InstructionSet};
+
+    #[test]
+    fn sprite_widths() {
+        let mut crt = Crt::new(8, 1);
+        crt.finish(3);
+        assert_eq!(crt.lines(), ["..###..."]);
+
+        let mut crt = Crt::new(8, 1).with_sprite_width(4).with_glyphs('█', ' ');
+        crt.finish(3);
+        assert_eq!(crt.lines(), ["  ████  "]);
+
+        let mut crt = Crt::new(4, 1).with_sprite_width(1);
+        crt.finish(-1);
+        assert_eq!(crt.lines(), ["...."]);
+    }
+
+    #[test]
+    fn packed_rows() {
+        let mut crt = Crt::default();
+        crt.finish(0);
+        assert_eq!(
+            format!("{:040b}", crt.packed_rows().unwrap()[0]),
+            "1100000000000000000000000000000000000000"
+        );
+        let mut crt = Crt::default();
+        crt.finish(39);
+        assert_eq!(
+            format!("{:040b}", crt.packed_rows().unwrap()[5]),
+            "0000000000000000000000000000000000000011"
+        );
+        assert_eq!(Crt::new(65, 1).packed_rows(), None);
+    }
+
+    #[test]
+    fn programs_ending_early() {
+        let mut crt = Crt::new(5, 2);
+        crate::draw(&mut crt, "addx 2\nnoop").unwrap();
+        assert!(crt.is_complete());
+        // X is 1 for cycles 1 and 2, then 3 for the rest of the screen
+        assert_eq!(crt.to_string(), "#####\n..###\n");
+        assert_eq!(
+            crt.grid(),
+            [
+                [true, true, true, true, true],
+                [false, false, true, true, true]
+            ]
+        );
+        assert!(crt.is_lit(1, 4) && !crt.is_lit(2, 0));
+    }
+
+    #[test]
+    fn programs_longer_than_the_screen() {
+        let mut crt = Crt::new(2, 2);
+        let mut cpu =
+            Cpu::from_source(&InstructionSet::standard(), "addx 5\nnoop\nnoop\nnoop").unwrap();
+        let mut cycles = 0;
+        let mut count = |_: &Cycle| {
+            cycles += 1;
+            Control::Continue
+        };
+        cpu.run(&mut [&mut crt, &mut count]);
+        assert!(cpu.is_finished());
+        assert_eq!(cycles, 5);
+        assert_eq!(crt.lines(), ["##", ".."]);
+    }
+}
@@ -1,110 +1,73 @@
-// #![allow(unused_imports, dead_code, unused_variables, unused_mut)]
-
-use std::collections::VecDeque;
-use std::str::FromStr;
+use aoc_common::prelude::ParseError;
+
+pub mod cpu;
+pub mod crt;
+use cpu::{Control, Cpu, Cycle, InstructionSet, Observer, Value};
+use crt::Crt;
+
+/// Sum of the signal strengths, cycle number times `X`, during some cycles
+#[derive(Debug, Clone)]
+pub struct SignalStrengths {
+    cycles: Vec<u32>,
+    sum: Value,
+}
 
-mod cpu;
-use cpu::{Cpu, Instruction};
+impl Default for SignalStrengths {
+    /// 20th, 60th, 100th, 140th, 180th, and 220th cycles
+    fn default() -> Self {
+        Self::new(vec![20, 60, 100, 140, 180, 220])
+    }
+}
 
-pub fn sum_of_signal_strengths(input: &str) -> i32 {
-    // 20th, 60th, 100th, 140th, 180th, and 220th cycles
-    const MULT_CYCLES: [u32; 6] = [20, 60, 100, 140, 180, 220];
-    let mut commands = input
-        .lines()
-        .map(Instruction::from_str)
-        .collect::<Result<VecDeque<_>, _>>()
-        .unwrap();
-    let mut cpu = Cpu::default();
-    let mut res: i32 = 0;
+impl SignalStrengths {
+    pub fn new(mut cycles: Vec<u32>) -> Self {
+        cycles.sort_unstable();
+        Self { cycles, sum: 0 }
+    }
 
-    loop {
-        if MULT_CYCLES.contains(&cpu.cycle()) {
-            let sig_str = cpu.signal_strength();
-            res += sig_str;
-        }
-        // Reached the end of the cycles array
-        if MULT_CYCLES
-            .last()
-            .is_some_and(|&cycle| cycle == cpu.cycle())
-        {
-            break;
-        }
-        // Reached the end of the commands deque
-        if commands.is_empty() {
-            break;
-        }
+    pub fn sum(&self) -> Value {
+        self.sum
+    }
+}
 
-        // Main CPU cycle
-        cpu.tick();
-        // Check whether command was completed
-        if cpu.current_command().is_none() {
-            cpu.set_command(commands.pop_front());
+impl Observer for SignalStrengths {
+    fn cycle(&mut self, cycle: &Cycle) -> Control {
+        if self.cycles.binary_search(&cycle.number).is_ok() {
+            self.sum += Value::from(cycle.number) * cycle.registers.x();
         }
+        Control::Continue
     }
-
-    res
 }
 
-#[allow(dead_code)]
-fn sprite_value(pos: i32) -> u64 {
-    const DISPLAY_MASK: u64 = 0b1111111111111111111111111111111111111111;
-    const SPRITE: u64 = 0b1110000000000000000000000000000000000000;
-
-    let (shifted_sprite, _left) = if pos <= 0 {
-        let shift = -(pos - 1);
-        SPRITE.overflowing_shl(shift.try_into().unwrap())
-    } else {
-        let shift = pos - 1;
-        SPRITE.overflowing_shr(shift.try_into().unwrap())
-    };
-
-    shifted_sprite & DISPLAY_MASK
+pub fn sum_of_signal_strengths(input: &str) -> Result<Value, ParseError> {
+    let mut strengths = SignalStrengths::default();
+    Cpu::from_source(&InstructionSet::standard(), input)?.run(&mut [&mut strengths]);
+    Ok(strengths.sum())
 }
 
-pub fn draw_crt(input: &str) -> Vec<String> {
-    const CRT_WIDTH: usize = 40;
-    const CRT_HEIGHT: usize = 6;
-    const CRT_PIXEL: char = '#';
-    const CRT_DOT: char = '.';
-
-    let mut crt_pixels: [[char; CRT_WIDTH]; CRT_HEIGHT] = [[CRT_DOT; CRT_WIDTH]; CRT_HEIGHT];
-    let mut commands = input
-        .lines()
-        .map(Instruction::from_str)
-        .collect::<Result<VecDeque<_>, _>>()
-        .unwrap();
-    let mut cpu = Cpu::default();
-
-    loop {
-        let crt_line_idx = cpu.cycle() as usize / CRT_WIDTH;
-        let crt_pixel_idx = cpu.cycle() as usize - crt_line_idx * CRT_WIDTH;
-
-        let crt_pixel = &mut crt_pixels[crt_line_idx][crt_pixel_idx];
-        let sprite_position: [i32; 3] = [
-            crt_pixel_idx as i32 - 1,
-            crt_pixel_idx as i32,
-            crt_pixel_idx as i32 + 1,
-        ];
-        // Main CPU cycle
-        cpu.tick();
-        if cpu.current_command().is_none() {
-            cpu.set_command(commands.pop_front());
-        }
-
-        if sprite_position.contains(&cpu.reg_x()) {
-            *crt_pixel = CRT_PIXEL;
-        }
+/// Runs `input` on `crt`, drawing the pixels left once the program ends with
+/// the last value of `X`
+pub fn draw(crt: &mut Crt, input: &str) -> Result<(), ParseError> {
+    let mut cpu = Cpu::from_source(&InstructionSet::standard(), input)?;
+    cpu.run(&mut [crt]);
+    crt.finish(cpu.registers().x());
+    Ok(())
+}
 
-        // Reached the end of commands list
-        if commands.is_empty() {
-            break;
-        }
-    }
+pub fn draw_crt(input: &str) -> Result<Vec<String>, ParseError> {
+    let mut crt = Crt::default();
+    draw(&mut crt, input)?;
+    Ok(crt.lines())
+}
 
-    crt_pixels
-        .into_iter() // iterator over arrays of char40
-        .map(String::from_iter) // char40 array to string
-        .collect::<Vec<String>>()
+/// Both parts from a single run of the program
+pub fn solve(input: &str) -> Result<(Value, Vec<String>), ParseError> {
+    let mut strengths = SignalStrengths::default();
+    let mut crt = Crt::default();
+    let mut cpu = Cpu::from_source(&InstructionSet::standard(), input)?;
+    cpu.run(&mut [&mut strengths, &mut crt]);
+    crt.finish(cpu.registers().x());
+    Ok((strengths.sum(), crt.lines()))
 }
 
 #[allow(unused_imports)]
@@ -122,20 +85,20 @@ mod tests {
         fn small_input_zero() {
             use constants::TEST_SMALL;
             let result = sum_of_signal_strengths(TEST_SMALL);
-            assert_eq!(result, 0i32);
+            assert_eq!(result, Ok(0));
         }
 
         #[test]
         fn large_input_non_zero() {
             use constants::TEST_LARGE;
             let result = sum_of_signal_strengths(TEST_LARGE);
-            assert_eq!(result, 13140i32);
+            assert_eq!(result, Ok(13140));
         }
 
         #[test]
         fn large_crt() {
             use constants::{CRT_LARGE, TEST_LARGE};
-            let result = draw_crt(TEST_LARGE);
+            let result = draw_crt(TEST_LARGE).unwrap();
             assert_eq!(result, CRT_LARGE.lines().collect::<Vec<_>>());
         }
 
@@ -143,13 +106,13 @@ mod tests {
         fn part_1() {
             let input = include_str!("../input.txt");
             let result = sum_of_signal_strengths(input);
-            assert_eq!(result, 14820i32);
+            assert_eq!(result, Ok(14820));
         }
 
         #[test]
         fn part_2() {
             let input = include_str!("../input.txt");
-            let result = draw_crt(input);
+            let result = draw_crt(input).unwrap();
             assert_eq!(
                 result,
                 CRT_PART_2_ANSWER_RZEKEFHA
@@ -158,60 +121,15 @@ mod tests {
                     .collect::<Vec<_>>()
             );
         }
-    }
-    mod bitwise {
-        use super::*;
-        use pretty_assertions::assert_eq;
 
         #[test]
-        fn test_sprite_value_minus_1() {
-            assert_eq!(
-                format!("{:040b}", sprite_value(-1)),
-                "1000000000000000000000000000000000000000"
-            );
-        }
-
-        #[test]
-        fn test_sprite_value_0() {
-            assert_eq!(
-                format!("{:040b}", sprite_value(0)),
-                "1100000000000000000000000000000000000000"
-            );
-        }
-
-        #[test]
-        fn test_sprite_value_1() {
-            assert_eq!(
-                format!("{:040b}", sprite_value(1)),
-                "1110000000000000000000000000000000000000"
-            );
-        }
-
-        #[test]
-        fn test_sprite_value_38() {
-            assert_eq!(
-                format!("{:040b}", sprite_value(38)),
-                "0000000000000000000000000000000000000111"
-            );
-        }
-
-        #[test]
-        fn test_sprite_value_39() {
-            assert_eq!(
-                format!("{:040b}", sprite_value(39)),
-                "0000000000000000000000000000000000000011"
-            );
-        }
-
-        #[test]
-        fn test_sprite_value_40() {
-            assert_eq!(
-                format!("{:040b}", sprite_value(40)),
-                "0000000000000000000000000000000000000001"
-            );
+        fn both_parts_in_one_run() {
+            use constants::{CRT_LARGE, TEST_LARGE};
+            let (strengths, crt) = solve(TEST_LARGE).unwrap();
+            assert_eq!(strengths, 13140);
+            assert_eq!(crt, CRT_LARGE.lines().collect::<Vec<_>>());
         }
     }
-
     mod constants {
         pub const TEST_SMALL: &str = "noop
 addx 3
@@ -1,7 +1,39 @@
-use cathode_ray_tube::{draw_crt, sum_of_signal_strengths};
+use cathode_ray_tube::{
+    cpu::{Cpu, InstructionSet, Trace},
+    solve,
+};
+
+const USAGE: &str = "usage: cathode_ray_tube [trace]";
 
 fn main() {
-    let input = include_str!("../input.txt");
-    println!("Part 1: {}", sum_of_signal_strengths(input));
-    println!("Part 2:\n{}", draw_crt(input).join("\n"));
+    let input = &aoc_common::puzzle_input!("../input.txt");
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        [] => match solve(input) {
+            Ok((part_1, part_2)) => {
+                println!("Part 1: {part_1}");
+                println!("Part 2:\n{}", part_2.join("\n"));
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        },
+        ["trace"] => match Cpu::from_source(&InstructionSet::standard(), input) {
+            Ok(mut cpu) => {
+                let mut trace = Trace::default();
+                cpu.run(&mut [&mut trace]);
+                print!("{trace}");
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
 }
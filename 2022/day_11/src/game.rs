@@ -0,0 +1,199 @@
+//! Round by round engine behind [`crate::play`], for looking at the game
+//! while it goes
+
+use std::collections::VecDeque;
+
+use log::debug;
+
+use crate::monkey::{Item, Monkey, MonkeyError, OperationError, Operator, Worry, WorryLevel};
+use crate::Relief;
+
+/// An item by where it starts: the index of its monkey and its position in
+/// the starting items
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemId {
+    pub monkey: usize,
+    pub index: usize,
+}
+
+impl std::fmt::Display for ItemId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "item {} of monkey {}", self.index, self.monkey)
+    }
+}
+
+/// An item thrown after an inspection
+#[derive(Debug)]
+pub struct Throw<'a, W> {
+    pub round: u32,
+    pub item: ItemId,
+    /// Index of the monkey which inspected the item
+    pub from: usize,
+    pub to: usize,
+    /// Worry level after the inspection and the relief
+    pub worry: &'a W,
+}
+
+/// Monkeys playing with worry levels computed as `W`
+pub struct Game<'a, W: Worry> {
+    monkeys: &'a [Monkey],
+    context: W::Context,
+    /// Divisor and its value as `W`
+    relief: Option<(WorryLevel, W)>,
+    items: Vec<VecDeque<(ItemId, W)>>,
+    inspections: Vec<WorryLevel>,
+    round: u32,
+}
+
+impl<'a, W: Worry> Game<'a, W> {
+    /// Game on the items the monkeys start with, an error if `W` can't play
+    /// it or a monkey throws to one that doesn't exist
+    pub fn new(monkeys: &'a [Monkey], relief: Relief) -> Result<Self, MonkeyError> {
+        let context = W::context(monkeys)?;
+        let relief = match relief {
+            Relief::None => None,
+            Relief::DivideBy(_) if !W::supports(Operator::Divide) => {
+                return Err(MonkeyError::Unsupported {
+                    backend: W::NAME,
+                    what: "divide worry levels for relief".to_owned(),
+                })
+            }
+            Relief::DivideBy(0) => return Err(OperationError::ZeroDivision.into()),
+            Relief::DivideBy(divisor) => Some((divisor, W::from_level(divisor, &context))),
+        };
+        for monkey in monkeys {
+            let (if_true, if_false) = monkey.test().targets();
+            if let Some(to) = [if_true, if_false]
+                .into_iter()
+                .find(|&to| to >= monkeys.len())
+            {
+                return Err(MonkeyError::NoSuchMonkey {
+                    from: monkey.id(),
+                    to,
+                });
+            }
+        }
+
+        let items = monkeys
+            .iter()
+            .enumerate()
+            .map(|(monkey, m)| {
+                m.items()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &item)| {
+                        let id = ItemId { monkey, index };
+                        (id, W::from_level(item.into(), &context))
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Self {
+            monkeys,
+            context,
+            relief,
+            items,
+            inspections: vec![0; monkeys.len()],
+            round: 0,
+        })
+    }
+
+    /// Rounds played so far
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    /// Items inspected by every monkey so far
+    pub fn inspections(&self) -> &[WorryLevel] {
+        &self.inspections
+    }
+
+    /// Worry levels of the items every monkey holds, in throwing order
+    pub fn items(&self) -> impl Iterator<Item = impl Iterator<Item = &W>> {
+        self.items
+            .iter()
+            .map(|items| items.iter().map(|(_, worry)| worry))
+    }
+
+    /// Plays one round, telling `on_throw` about every item thrown
+    pub fn play_round(&mut self, on_throw: &mut dyn FnMut(&Throw<W>)) -> Result<(), MonkeyError> {
+        self.round += 1;
+        debug!(
+            "-------------------------------Round {}------------------------------",
+            self.round
+        );
+        for (monkey_idx, monkey) in self.monkeys.iter().enumerate() {
+            debug!("Monkey {}:", monkey.id());
+            while let Some((item, worry)) = self.items[monkey_idx].pop_front() {
+                debug!("  Monkey inspects an item with a worry level of {worry}");
+                let mut worry = monkey.operation().evaluate_worry(&worry, &self.context)?;
+                self.inspections[monkey_idx] += 1;
+                debug!(
+                    "    Worry level becomes {worry} with {}",
+                    monkey.operation()
+                );
+                if let Some((divisor, relief)) = &self.relief {
+                    worry = worry
+                        .apply(Operator::Divide, relief)
+                        .expect("relief divisor is not zero");
+                    debug!(
+                        "    Monkey gets bored with item. Worry level is divided by {divisor} to {worry}."
+                    );
+                }
+
+                let predicate = monkey.test().predicate();
+                let holds = worry
+                    .test(predicate)
+                    .ok_or_else(|| MonkeyError::Unsupported {
+                        backend: W::NAME,
+                        what: format!("test if {predicate}"),
+                    })?;
+                let (if_true, if_false) = monkey.test().targets();
+                let to = if holds { if_true } else { if_false };
+                debug!("    Item with worry level {worry} is thrown to monkey {to}");
+                on_throw(&Throw {
+                    round: self.round,
+                    item,
+                    from: monkey_idx,
+                    to,
+                    worry: &worry,
+                });
+                self.items[to].push_back((item, worry));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sorted worry levels of every monkey, which is all the rounds to come
+    /// depend on since items move independently of each other
+    pub fn state(&self) -> Vec<Vec<W>>
+    where
+        W: Ord,
+    {
+        self.items()
+            .map(|items| {
+                let mut items = items.cloned().collect::<Vec<_>>();
+                items.sort_unstable();
+                items
+            })
+            .collect()
+    }
+
+    /// Items left with every monkey, an error if one doesn't fit in a `u64`
+    pub fn into_items(self) -> Result<Vec<VecDeque<Item>>, MonkeyError> {
+        self.items
+            .into_iter()
+            .map(|items| {
+                items
+                    .into_iter()
+                    .map(|(_, worry)| {
+                        worry
+                            .to_level()
+                            .map(Item::from)
+                            .ok_or_else(|| MonkeyError::TooLarge(worry.to_string()))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
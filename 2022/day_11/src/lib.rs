@@ -1,14 +1,62 @@
+use std::collections::HashMap;
+
 use log::debug;
 use monkey::Monkey;
 use nom::Finish;
+use num_bigint::BigUint;
+
+use crate::game::{Game, ItemId, Throw};
+use crate::monkey::{parse_monkeys, MonkeyError, PrettyMonkeysItems, Residue, Worry, WorryLevel};
 
-use crate::monkey::{parse_monkeys, PrettyMonkeysEvalCount, PrettyMonkeysItems, WorryLevel};
+pub mod game;
+pub mod monkey;
 
-mod monkey;
+/// What happens to the worry level after a monkey inspected an item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relief {
+    /// Worry level stays as the operation left it
+    None,
+    /// Worry level is divided by the value, rounded down
+    DivideBy(WorryLevel),
+}
 
+/// Number type the worry levels are computed with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// `u64`, an error on overflow
+    Checked,
+    /// `BigUint`, exact however big the worry levels get
+    Exact,
+    /// Residues modulo the LCM of the test divisors, which keeps the game
+    /// the same without growing but can't divide or compare
+    Modular,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Settings {
-    rounds: u32,
-    divide_by_3: bool,
+    pub rounds: u32,
+    pub relief: Relief,
+    pub backend: Backend,
+}
+
+impl Settings {
+    /// 20 rounds, worry levels divided by 3
+    pub fn part1() -> Self {
+        Self {
+            rounds: 20,
+            relief: Relief::DivideBy(3),
+            backend: Backend::Checked,
+        }
+    }
+
+    /// 10000 rounds without relief
+    pub fn part2() -> Self {
+        Self {
+            rounds: 10000,
+            relief: Relief::None,
+            backend: Backend::Modular,
+        }
+    }
 }
 
 #[allow(unused_macros)]
@@ -25,121 +73,266 @@ macro_rules! function {
     }};
 }
 
-pub fn get_monkey_business(monkeys: &[Monkey]) -> WorryLevel {
-    let mut monkeys = Vec::from(monkeys);
-    monkeys.sort_by_key(|m| m.evaluations_count());
-    monkeys
+/// Product of the inspection counts of the `top` most active monkeys
+pub fn get_monkey_business(monkeys: &[Monkey], top: usize) -> u128 {
+    let mut counts = monkeys
         .iter()
-        .rev()
-        .take(2)
         .map(|m| m.evaluations_count())
-        .product()
+        .collect::<Vec<_>>();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    counts.into_iter().take(top).map(u128::from).product()
 }
 
-pub fn play(monkeys: &mut [Monkey], settings: Settings) {
-    let divisor_product = monkeys
-        .iter()
-        .map(|m| m.test().operation().value().value().unwrap_or_default())
-        .product::<WorryLevel>();
-    dbg!(divisor_product);
-
-    for round in 1..=settings.rounds {
-        debug!("-------------------------------Round {round}------------------------------");
-        for monkey_idx in 0..monkeys.len() {
-            let mut monkey = monkeys.get(monkey_idx).cloned().unwrap();
-
-            debug!("Monkey {}:", monkey.id());
-            while let Some(mut item) = monkey.items_mut().pop_front() {
-                debug!("  Monkey inspects an item with a worry level of {}", item);
-                if !settings.divide_by_3 {
-                    item %= divisor_product.into();
-                    debug!("  Divisor product applied {}", item);
-                }
+/// Calls `$body` with `$worry` the type of the backend
+macro_rules! with_backend {
+    ($backend:expr, $worry:ident => $body:expr) => {
+        match $backend {
+            Backend::Checked => {
+                type $worry = WorryLevel;
+                $body
+            }
+            Backend::Exact => {
+                type $worry = BigUint;
+                $body
+            }
+            Backend::Modular => {
+                type $worry = Residue;
+                $body
+            }
+        }
+    };
+}
 
-                let worry_level = monkey.operation().evaluate(item);
-                *monkey.evaluations_count_mut() += 1;
-                debug!(
-                    "    Worry level is {} to {}",
-                    monkey.operation(),
-                    worry_level
-                );
-                let mut worry_level = worry_level;
-                if settings.divide_by_3 {
-                    worry_level /= 3;
-                    debug!(
-                        "    Monkey gets bored with item. Worry level is divided by 3 to {}.",
-                        worry_level
-                    );
-                }
+/// Plays `settings.rounds` rounds, counting inspections and leaving every
+/// monkey with the items it holds at the end.
+///
+/// With [`Backend::Modular`] the items left are residues, and with
+/// [`Backend::Exact`] the game fails at the end if one of them doesn't fit in
+/// a `u64`.
+pub fn play(monkeys: &mut [Monkey], settings: Settings) -> Result<(), MonkeyError> {
+    let (inspections, items) = with_backend!(settings.backend, W => {
+        let mut game = Game::<W>::new(monkeys, settings.relief)?;
+        for _ in 0..settings.rounds {
+            game.play_round(&mut |_| {})?;
+        }
+        (game.inspections().to_vec(), game.into_items()?)
+    });
+    for ((monkey, inspections), items) in monkeys.iter_mut().zip(inspections).zip(items) {
+        *monkey.evaluations_count_mut() += inspections;
+        *monkey.items_mut() = items;
+    }
+    debug!("{:#?}", PrettyMonkeysItems(monkeys));
+    Ok(())
+}
 
-                item.set(worry_level as WorryLevel);
-                let throw_to = monkey.test().apply(worry_level);
+/// Inspections of every monkey after each round, the first entry after
+/// round 1
+pub fn inspection_history(
+    monkeys: &[Monkey],
+    settings: Settings,
+) -> Result<Vec<Vec<WorryLevel>>, MonkeyError> {
+    with_backend!(settings.backend, W => {
+        let mut game = Game::<W>::new(monkeys, settings.relief)?;
+        (0..settings.rounds)
+            .map(|_| {
+                game.play_round(&mut |_| {})?;
+                Ok(game.inspections().to_vec())
+            })
+            .collect()
+    })
+}
+
+/// One inspection of an item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub round: u32,
+    /// Index of the monkey inspecting the item
+    pub monkey: usize,
+    pub thrown_to: usize,
+    /// Worry level once thrown, none if it doesn't fit in a `u64`
+    pub worry: Option<WorryLevel>,
+}
 
-                debug!("    Item with worry level {item} is thrown to monkey {throw_to}");
+/// Where an item went during a game
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Journey {
+    pub item: ItemId,
+    pub steps: Vec<Step>,
+}
+
+impl Journey {
+    /// Monkey holding the item at the end
+    pub fn holder(&self) -> usize {
+        self.steps
+            .last()
+            .map_or(self.item.monkey, |step| step.thrown_to)
+    }
 
-                monkeys
-                    .get_mut(throw_to)
-                    .unwrap()
-                    .items_mut()
-                    .push_back(item);
+    /// Monkeys which inspected the item, in order and as often as they did
+    pub fn monkeys(&self) -> impl Iterator<Item = usize> + '_ {
+        self.steps.iter().map(|step| step.monkey)
+    }
+
+    /// Monkeys which inspected the item in round `round`
+    pub fn round(&self, round: u32) -> impl Iterator<Item = usize> + '_ {
+        self.steps
+            .iter()
+            .filter(move |step| step.round == round)
+            .map(|step| step.monkey)
+    }
+}
+
+impl std::fmt::Display for Journey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Journey of {}:", self.item)?;
+        for step in &self.steps {
+            write!(
+                f,
+                "  Round {}: monkey {} throws it to monkey {}",
+                step.round, step.monkey, step.thrown_to
+            )?;
+            match step.worry {
+                Some(worry) => writeln!(f, " with a worry level of {worry}")?,
+                None => writeln!(f)?,
             }
-            // place monkey copy(with modified items list) back to Vec
-            *monkeys.get_mut(monkey_idx).unwrap() = monkey;
         }
-        debug!("{:#?}", PrettyMonkeysItems(monkeys));
-        // if [
-        //     1, 20, 1000, 2000, 3000, 4000, 5000, 6000, 7000, 8000, 9000, 10000,
-        // ]
-        // .contains(&round)
-        // {
-        //     println!(
-        //         "After round {}:\n{:#?}",
-        //         round,
-        //         PrettyMonkeysEvalCount(monkeys)
-        //     );
-        // }
-    }
-    println!("After round 20:\n{:#?}", PrettyMonkeysEvalCount(monkeys));
-    debug!("{:#?}", PrettyMonkeysItems(monkeys));
+        Ok(())
+    }
 }
 
-pub fn part1() -> WorryLevel {
-    let mut monkeys = parse_monkeys(include_str!("../input.txt"))
-        .finish()
-        .unwrap()
-        .1;
-    play(
-        &mut monkeys,
-        Settings {
-            rounds: 20,
-            divide_by_3: true,
-        },
-    );
-    println!("{:#?}", PrettyMonkeysItems(&monkeys));
-    println!("{:#?}", PrettyMonkeysEvalCount(&monkeys));
-    get_monkey_business(&monkeys)
+/// Journeys of every item, sorted by [`ItemId`]
+pub fn journeys(monkeys: &[Monkey], settings: Settings) -> Result<Vec<Journey>, MonkeyError> {
+    let mut journeys = monkeys
+        .iter()
+        .enumerate()
+        .flat_map(|(monkey, m)| {
+            (0..m.items().len()).map(move |index| Journey {
+                item: ItemId { monkey, index },
+                steps: Vec::new(),
+            })
+        })
+        .collect::<Vec<_>>();
+    with_backend!(settings.backend, W => {
+        let mut game = Game::<W>::new(monkeys, settings.relief)?;
+        for _ in 0..settings.rounds {
+            game.play_round(&mut |throw: &Throw<W>| {
+                let journey = journeys
+                    .binary_search_by_key(&throw.item, |journey| journey.item)
+                    .expect("items are never lost");
+                journeys[journey].steps.push(Step {
+                    round: throw.round,
+                    monkey: throw.from,
+                    thrown_to: throw.to,
+                    worry: throw.worry.to_level(),
+                });
+            })?;
+        }
+    });
+    Ok(journeys)
+}
+
+/// Journey of a single item
+pub fn trace_item(
+    monkeys: &[Monkey],
+    settings: Settings,
+    item: ItemId,
+) -> Result<Journey, MonkeyError> {
+    journeys(monkeys, settings)?
+        .into_iter()
+        .find(|journey| journey.item == item)
+        .ok_or(MonkeyError::NoSuchItem(item))
+}
+
+/// Rounds after which the game repeats itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// First round of the cycle, the state after it is the same as after
+    /// `start + period`
+    pub start: u32,
+    pub period: u32,
 }
 
-pub fn part2() -> WorryLevel {
+/// Inspections after a number of rounds, extrapolated from a cycle if the
+/// game repeats itself before the end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastForward {
+    pub inspections: Vec<WorryLevel>,
+    pub cycle: Option<Cycle>,
+    /// Rounds actually played
+    pub simulated: u32,
+}
+
+/// Plays until the worry levels of every monkey are the same as after an
+/// earlier round, then works the inspections after `settings.rounds` out
+/// from the cycle instead of playing them.
+///
+/// With [`Backend::Modular`] the worry levels are residues, so the game has
+/// a finite number of states and is bound to repeat, but the cycle can be
+/// longer than `settings.rounds`.
+pub fn fast_forward(monkeys: &[Monkey], settings: Settings) -> Result<FastForward, MonkeyError> {
+    with_backend!(settings.backend, W => {
+        let mut game = Game::<W>::new(monkeys, settings.relief)?;
+        let mut seen = HashMap::from([(game.state(), 0)]);
+        let mut history = vec![game.inspections().to_vec()];
+        while game.round() < settings.rounds {
+            game.play_round(&mut |_| {})?;
+            history.push(game.inspections().to_vec());
+            let Some(&start) = seen.get(&game.state()) else {
+                seen.insert(game.state(), game.round());
+                continue;
+            };
+            let (end, period) = (game.round(), game.round() - start);
+            let (full, rest) = (
+                (settings.rounds - end) / period,
+                (settings.rounds - end) % period,
+            );
+            let (at_start, at_end, at_rest) = (
+                &history[start as usize],
+                &history[end as usize],
+                &history[(start + rest) as usize],
+            );
+            let inspections = (0..monkeys.len())
+                .map(|m| {
+                    at_end[m]
+                        + WorryLevel::from(full) * (at_end[m] - at_start[m])
+                        + (at_rest[m] - at_start[m])
+                })
+                .collect();
+            return Ok(FastForward {
+                inspections,
+                cycle: Some(Cycle { start, period }),
+                simulated: end,
+            });
+        }
+        Ok(FastForward {
+            inspections: game.inspections().to_vec(),
+            cycle: None,
+            simulated: game.round(),
+        })
+    })
+}
+
+fn solve(settings: Settings) -> Result<u128, MonkeyError> {
     let mut monkeys = parse_monkeys(include_str!("../input.txt"))
         .finish()
         .unwrap()
         .1;
-    play(
-        &mut monkeys,
-        Settings {
-            rounds: 10000,
-            divide_by_3: false,
-        },
-    );
-    println!("{:#?}", PrettyMonkeysItems(&monkeys));
-    println!("{:#?}", PrettyMonkeysEvalCount(&monkeys));
-    get_monkey_business(&monkeys)
+    play(&mut monkeys, settings)?;
+    Ok(get_monkey_business(&monkeys, 2))
+}
+
+pub fn part1() -> Result<u128, MonkeyError> {
+    solve(Settings::part1())
+}
+
+pub fn part2() -> Result<u128, MonkeyError> {
+    solve(Settings::part2())
 }
 
 #[cfg(test)]
 mod test {
-    use crate::monkey::{parse_monkeys, PrettyMonkeysEvalCount};
+    use crate::game::ItemId;
+    use crate::monkey::{parse_monkeys, OperationError};
 
     use super::*;
     use env_logger::Env;
@@ -163,7 +356,7 @@ mod test {
             })
             .try_init();
         if let Err(e) = log_init_res {
-            warn!("{}", e.to_string());
+            warn!("{e}");
         }
     }
 
@@ -171,44 +364,233 @@ mod test {
     fn play_test_input_1() {
         init_log();
         let mut monkeys = parse_monkeys(constants::MONKEY_INPUT).finish().unwrap().1;
-        play(
-            &mut monkeys,
-            Settings {
-                rounds: 20,
-                divide_by_3: true,
-            },
-        );
-        println!("{:#?}", PrettyMonkeysItems(&monkeys));
-        println!("{:#?}", PrettyMonkeysEvalCount(&monkeys));
-        assert_eq!(get_monkey_business(&monkeys), 10605);
+        play(&mut monkeys, Settings::part1()).unwrap();
+        assert_eq!(get_monkey_business(&monkeys, 2), 10605);
     }
 
     #[test]
     fn play_test_input_2() {
         init_log();
         let mut monkeys = parse_monkeys(constants::MONKEY_INPUT).finish().unwrap().1;
-        play(
-            &mut monkeys,
+        play(&mut monkeys, Settings::part2()).unwrap();
+        assert_eq!(get_monkey_business(&monkeys, 2), 2713310158);
+    }
+
+    fn play_sample(settings: Settings) -> Result<Vec<Monkey>, MonkeyError> {
+        let mut monkeys = parse_monkeys(constants::MONKEY_INPUT).finish().unwrap().1;
+        play(&mut monkeys, settings).map(|()| monkeys)
+    }
+
+    #[test]
+    fn backends_agree() {
+        let checked = play_sample(Settings::part1()).unwrap();
+        let exact = play_sample(Settings {
+            backend: Backend::Exact,
+            ..Settings::part1()
+        })
+        .unwrap();
+        assert_eq!(checked, exact);
+
+        let settings = Settings {
+            rounds: 50,
+            relief: Relief::None,
+            backend: Backend::Exact,
+        };
+        let exact = play_sample(settings).unwrap_err();
+        assert!(matches!(exact, MonkeyError::TooLarge(_)));
+        let checked = play_sample(Settings {
+            rounds: 3,
+            backend: Backend::Checked,
+            ..settings
+        })
+        .unwrap();
+        let modular = play_sample(Settings {
+            rounds: 3,
+            backend: Backend::Modular,
+            ..settings
+        })
+        .unwrap();
+        let counts = |monkeys: &[Monkey]| {
+            monkeys
+                .iter()
+                .map(Monkey::evaluations_count)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(counts(&checked), counts(&modular));
+        assert!(matches!(
+            play_sample(Settings {
+                backend: Backend::Checked,
+                ..Settings::part2()
+            }),
+            Err(MonkeyError::InvalidOperation(
+                OperationError::OutOfRange { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn relief_and_top_monkeys() {
+        let monkeys = play_sample(Settings::part1()).unwrap();
+        // 101, 95, 7 and 105 inspections
+        assert_eq!(get_monkey_business(&monkeys, 1), 105);
+        assert_eq!(get_monkey_business(&monkeys, 3), 105 * 101 * 95);
+        assert_eq!(get_monkey_business(&monkeys, 10), 105 * 101 * 95 * 7);
+        assert_eq!(get_monkey_business(&monkeys, 0), 1);
+
+        assert!(matches!(
+            play_sample(Settings {
+                relief: Relief::DivideBy(3),
+                ..Settings::part2()
+            }),
+            Err(MonkeyError::Unsupported {
+                backend: "modular",
+                ..
+            })
+        ));
+        assert_eq!(
+            play_sample(Settings {
+                relief: Relief::DivideBy(0),
+                ..Settings::part1()
+            }),
+            Err(OperationError::ZeroDivision.into())
+        );
+        let halved = Settings {
+            rounds: 5,
+            relief: Relief::DivideBy(2),
+            backend: Backend::Exact,
+        };
+        assert_eq!(
+            play_sample(halved).unwrap(),
+            play_sample(Settings {
+                backend: Backend::Checked,
+                ..halved
+            })
+            .unwrap()
+        );
+        // Halving isn't enough to keep the worry levels in a u64
+        assert!(matches!(
+            play_sample(Settings {
+                rounds: 20,
+                ..halved
+            }),
+            Err(MonkeyError::TooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn journeys_and_histories() {
+        let monkeys = parse_monkeys(constants::MONKEY_INPUT).finish().unwrap().1;
+        let first = ItemId {
+            monkey: 0,
+            index: 0,
+        };
+        let journey = trace_item(&monkeys, Settings::part1(), first).unwrap();
+        assert_eq!(
+            journey.steps[..2],
+            [
+                Step {
+                    round: 1,
+                    monkey: 0,
+                    thrown_to: 3,
+                    worry: Some(500)
+                },
+                Step {
+                    round: 1,
+                    monkey: 3,
+                    thrown_to: 1,
+                    worry: Some(167)
+                }
+            ]
+        );
+        assert_eq!(journey.round(1).collect::<Vec<_>>(), [0, 3]);
+        assert_eq!(journey.monkeys().count(), journey.steps.len());
+        assert!(journey
+            .to_string()
+            .contains("Round 1: monkey 0 throws it to monkey 3 with a worry level of 500"));
+        let everyone = journeys(&monkeys, Settings::part1()).unwrap();
+        assert_eq!(everyone.len(), 10);
+        assert_eq!(everyone[0], journey);
+        assert_eq!(
+            trace_item(
+                &monkeys,
+                Settings::part1(),
+                ItemId {
+                    monkey: 3,
+                    index: 1
+                }
+            ),
+            Err(MonkeyError::NoSuchItem(ItemId {
+                monkey: 3,
+                index: 1
+            }))
+        );
+
+        let history = inspection_history(
+            &monkeys,
             Settings {
-                rounds: 10000,
-                divide_by_3: false,
+                rounds: 20,
+                ..Settings::part2()
             },
-        );
-        println!("{:#?}", PrettyMonkeysItems(&monkeys));
-        println!("{:#?}", PrettyMonkeysEvalCount(&monkeys));
-        assert_eq!(get_monkey_business(&monkeys), 2713310158);
+        )
+        .unwrap();
+        assert_eq!(history.len(), 20);
+        assert_eq!(history[0], [2, 4, 3, 6]);
+        assert_eq!(history[19], [99, 97, 8, 103]);
+    }
+
+    #[test]
+    fn fast_forwards() {
+        let counts = |input: &str, settings: Settings| {
+            let mut monkeys = parse_monkeys(input).finish().unwrap().1;
+            let fast = fast_forward(&monkeys, settings).unwrap();
+            play(&mut monkeys, settings).unwrap();
+            let played = monkeys
+                .iter()
+                .map(Monkey::evaluations_count)
+                .collect::<Vec<_>>();
+            assert_eq!(fast.inspections, played);
+            fast
+        };
+        let fast = counts(constants::MONKEY_INPUT, Settings::part2());
+        assert_eq!(fast.inspections, [52166, 47830, 1938, 52013]);
+
+        let input = "Monkey 0:
+  Starting items: 1, 2
+  Operation: new = old + 1
+  Test: divisible by 2
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 5
+  Operation: new = old * 2
+  Test: divisible by 3
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+";
+        let fast = counts(input, Settings::part2());
+        let cycle = fast.cycle.unwrap();
+        assert!(fast.simulated <= cycle.start + cycle.period);
+        assert!(fast.simulated < 100);
+        for rounds in [1, 7, 99, 1000] {
+            let settings = Settings {
+                rounds,
+                ..Settings::part2()
+            };
+            counts(input, settings);
+        }
     }
 
     #[test]
     fn play_part1() {
         init_log();
-        assert_eq!(part1(), 99852);
+        assert_eq!(part1(), Ok(99852));
     }
 
     #[test]
     fn play_part2() {
         init_log();
-        assert_eq!(part2(), 25935263541);
+        assert_eq!(part2(), Ok(25935263541));
     }
 
     mod constants {
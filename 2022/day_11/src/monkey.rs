@@ -1,20 +1,23 @@
 mod item;
 mod operation;
 mod test;
+mod worry;
 
-use std::{collections::VecDeque, str::FromStr};
+use std::collections::VecDeque;
 
+use crate::game::ItemId;
 pub use item::Item;
 use nom::{
     bytes::complete::{tag, take_until},
-    character::complete::{alphanumeric1, line_ending, one_of, space1},
-    combinator::{all_consuming, map, map_res, opt},
+    character::complete::{line_ending, space1},
+    combinator::{all_consuming, map, opt},
     multi::{many0, separated_list0},
-    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+    sequence::{delimited, pair, preceded, terminated, tuple},
     IResult,
 };
-pub use operation::{Operation, OperationError, Value};
-pub use test::Test;
+pub use operation::{Expression, Operation, OperationError, Operator, Value};
+pub use test::{Predicate, Test};
+pub use worry::{Residue, Worry};
 
 pub type WorryLevel = u64;
 pub type Monkeys = Vec<Monkey>;
@@ -69,10 +72,18 @@ impl Monkey {
     }
 }
 
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum MonkeyError {
     #[error(transparent)]
     InvalidOperation(#[from] OperationError),
+    #[error("the {backend} worry backend can't {what}")]
+    Unsupported { backend: &'static str, what: String },
+    #[error("worry level {0} doesn't fit in a u64")]
+    TooLarge(String),
+    #[error("monkey {from} throws to monkey {to} which doesn't exist")]
+    NoSuchMonkey { from: u32, to: usize },
+    #[error("there is no {0}")]
+    NoSuchItem(ItemId),
 }
 
 pub fn parse_items(input: &str) -> IResult<&str, Items> {
@@ -86,18 +97,15 @@ pub fn parse_items(input: &str) -> IResult<&str, Items> {
 }
 
 pub fn parse_operation(input: &str) -> IResult<&str, Operation> {
-    let (input, _) = preceded(space1, tag("Operation: new = old "))(input)?;
-    let (input, (operation, value)) =
-        separated_pair(one_of("+-*/"), tag(" "), alphanumeric1)(input)?;
-    // remove unwraps
-    let value = Value::from_str(value).unwrap();
-    let operation = Operation::new(operation, value).unwrap();
-    Ok((input, operation))
+    preceded(
+        pair(space1, tag("Operation: new = ")),
+        Operation::parse_operation,
+    )(input)
 }
 
 pub fn parse_test(input: &str) -> IResult<&str, Test> {
-    let divisible_by = terminated(
-        preceded(tag("  Test: divisible by "), nom::character::complete::u64),
+    let predicate = terminated(
+        preceded(tag("  Test: "), Predicate::parse_predicate),
         line_ending,
     );
     let if_true_throw_to = terminated(
@@ -112,15 +120,14 @@ pub fn parse_test(input: &str) -> IResult<&str, Test> {
         nom::character::complete::u32,
     );
 
-    map_res(
-        tuple((divisible_by, if_true_throw_to, if_false_throw_to)),
-        |(divisible_by, if_true_throw_to, if_false_throw_to)| {
-            let operation = Operation::new('/', divisible_by.into()).unwrap();
-            Ok::<Test, &str>(Test::new(
-                operation,
-                if_true_throw_to.try_into().unwrap(),
-                if_false_throw_to.try_into().unwrap(),
-            ))
+    map(
+        tuple((predicate, if_true_throw_to, if_false_throw_to)),
+        |(predicate, if_true_throw_to, if_false_throw_to)| {
+            Test::new(
+                predicate,
+                if_true_throw_to as usize,
+                if_false_throw_to as usize,
+            )
         },
     )(input)
 }
@@ -170,6 +177,7 @@ impl<'a> std::fmt::Debug for PrettyMonkeysEvalCount<'a> {
 #[cfg(test)]
 mod tests {
     use crate::monkey::{parse_monkeys, Item, Monkey, Operation, Value};
+    use std::str::FromStr;
 
     use super::*;
     use constants::*;
@@ -184,8 +192,8 @@ mod tests {
         let monkey_0 = Monkey {
             id: 0,
             items: vec![Item::from(79), Item::from(98)].into(),
-            operation: Operation::Multiply(Value::Const(19)),
-            test: Test::new(Operation::Divide(Value::Const(23)), 2, 3),
+            operation: Operation::new('*', Value::Const(19)).unwrap(),
+            test: Test::new(Predicate::DivisibleBy(23), 2, 3),
             evaluations_count: 0,
         };
         let monkey_1 = Monkey {
@@ -197,27 +205,47 @@ mod tests {
                 Item::from(74),
             ]
             .into(),
-            operation: Operation::Add(Value::Const(6)),
-            test: Test::new(Operation::Divide(Value::Const(19)), 2, 0),
+            operation: Operation::new('+', Value::Const(6)).unwrap(),
+            test: Test::new(Predicate::DivisibleBy(19), 2, 0),
             evaluations_count: 0,
         };
         let monkey_2 = Monkey {
             id: 2,
             items: vec![Item::from(79), Item::from(60), Item::from(97)].into(),
-            operation: Operation::Multiply(Value::Old),
-            test: Test::new(Operation::Divide(Value::Const(13)), 1, 3),
+            operation: Operation::from_str("old * old").unwrap(),
+            test: Test::new(Predicate::DivisibleBy(13), 1, 3),
             evaluations_count: 0,
         };
         let monkey_3 = Monkey {
             id: 3,
             items: vec![Item::from(74)].into(),
-            operation: Operation::Add(Value::Const(3)),
-            test: Test::new(Operation::Divide(Value::Const(17)), 0, 1),
+            operation: Operation::new('+', Value::Const(3)).unwrap(),
+            test: Test::new(Predicate::DivisibleBy(17), 0, 1),
             evaluations_count: 0,
         };
         assert_eq!(monkeys, vec![monkey_0, monkey_1, monkey_2, monkey_3]);
     }
 
+    #[test]
+    fn expressions_and_predicates() {
+        let input = "Monkey 0:
+  Starting items: 10
+  Operation: new = (old + 2) * old - 1
+  Test: greater than 100
+    If true: throw to monkey 1
+    If false: throw to monkey 0
+";
+        let (_r, monkeys) = parse_monkeys(input).finish().unwrap();
+        assert_eq!(monkeys[0].operation().evaluate(Item::from(10)), Ok(119));
+        assert_eq!(
+            monkeys[0].test(),
+            &Test::new(Predicate::GreaterThan(100), 1, 0)
+        );
+        assert!(parse_monkeys(&input.replace("old - 1", "old % 1"))
+            .finish()
+            .is_err());
+    }
+
     mod constants {
         pub const MONKEY_INPUT: &str = "Monkey 0:
   Starting items: 79, 98
@@ -1,11 +1,25 @@
-use super::{Item, WorryLevel};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{one_of, space0},
+    combinator::{all_consuming, map, opt, value},
+    multi::many0,
+    sequence::{delimited, pair, preceded, terminated},
+    Finish, IResult,
+};
 
-#[derive(Debug, thiserror::Error)]
+use super::{Item, Worry, WorryLevel};
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
 pub enum OperationError {
     #[error("zero division is not possible")]
     ZeroDivision,
     #[error("unsupported operation \"{0:?}\"")]
     Unsupported(char),
+    #[error("`{expression}` is out of range for old = {old}")]
+    OutOfRange { expression: String, old: String },
+    #[error("unable to parse \"{0}\" as an operation")]
+    ParsingFailed(String),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -28,12 +42,19 @@ impl Value {
         None
     }
 
-    fn value_or_old(&self, old: WorryLevel) -> WorryLevel {
+    fn value_or_old<W: Worry>(&self, old: &W, context: &W::Context) -> W {
         match self {
-            Value::Const(c) => *c,
-            Value::Old => old,
+            Value::Const(c) => W::from_level(*c, context),
+            Value::Old => old.clone(),
         }
     }
+
+    fn parse_value(input: &str) -> IResult<&str, Value> {
+        alt((
+            value(Value::Old, tag("old")),
+            map(nom::character::complete::u64, Value::Const),
+        ))(input)
+    }
 }
 
 impl From<WorryLevel> for Value {
@@ -60,71 +81,304 @@ impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Value::Const(c) => write!(f, "{c}"),
-            Value::Old => write!(f, "old value"),
+            Value::Old => write!(f, "old"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Operator {
+    pub fn new(operator: char) -> Result<Self, OperationError> {
+        match operator {
+            '+' => Ok(Self::Add),
+            '-' => Ok(Self::Subtract),
+            '*' => Ok(Self::Multiply),
+            '/' => Ok(Self::Divide),
+            _ => Err(OperationError::Unsupported(operator)),
+        }
+    }
+
+    pub fn symbol(&self) -> char {
+        match self {
+            Operator::Add => '+',
+            Operator::Subtract => '-',
+            Operator::Multiply => '*',
+            Operator::Divide => '/',
+        }
+    }
+
+    /// Binding strength, `*` and `/` before `+` and `-`
+    fn precedence(&self) -> u8 {
+        match self {
+            Operator::Add | Operator::Subtract => 1,
+            Operator::Multiply | Operator::Divide => 2,
         }
     }
 }
 
+/// Right hand side of a `new = ...` line
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
-pub enum Operation {
-    Multiply(Value),
-    Divide(Value),
-    Add(Value),
-    Subtract(Value),
+pub enum Expression {
+    Value(Value),
+    Binary(Operator, Box<Expression>, Box<Expression>),
 }
 
-impl Operation {
-    pub fn new(operation: char, value: Value) -> Result<Self, OperationError> {
-        match operation {
-            '+' => Ok(Self::Add(value)),
-            '-' => Ok(Self::Subtract(value)),
-            '*' => Ok(Self::Multiply(value)),
-            '/' => {
-                if value == Value::Const(0) {
+impl Expression {
+    pub fn binary(operator: Operator, lhs: Expression, rhs: Expression) -> Self {
+        Self::Binary(operator, Box::new(lhs), Box::new(rhs))
+    }
+
+    fn evaluate<W: Worry>(&self, old: &W, context: &W::Context) -> Result<W, OperationError> {
+        match self {
+            Expression::Value(value) => Ok(value.value_or_old(old, context)),
+            Expression::Binary(operator, lhs, rhs) => {
+                if !W::supports(*operator) {
+                    return Err(OperationError::Unsupported(operator.symbol()));
+                }
+                let (lhs, rhs) = (lhs.evaluate(old, context)?, rhs.evaluate(old, context)?);
+                if *operator == Operator::Divide && rhs.is_zero() {
                     return Err(OperationError::ZeroDivision);
                 }
-                Ok(Self::Divide(value))
+                lhs.apply(*operator, &rhs)
+                    .ok_or_else(|| OperationError::OutOfRange {
+                        expression: self.to_string(),
+                        old: old.to_string(),
+                    })
             }
-            _ => Err(OperationError::Unsupported(operation)),
         }
     }
 
-    pub fn evaluate(&self, other: Item) -> WorryLevel {
-        let other: WorryLevel = other.into();
-        let result = match self {
-            Operation::Multiply(v) => v.value_or_old(other).checked_mul(other),
-            Operation::Divide(v) => v.value_or_old(other).checked_div(other),
-            Operation::Add(v) => v.value_or_old(other).checked_add(other),
-            Operation::Subtract(v) => v.value_or_old(other).checked_sub(other),
+    fn parse_factor(input: &str) -> IResult<&str, Expression> {
+        delimited(
+            space0,
+            alt((
+                map(Value::parse_value, Expression::Value),
+                delimited(
+                    terminated(tag("("), space0),
+                    Expression::parse_sum,
+                    preceded(space0, tag(")")),
+                ),
+            )),
+            space0,
+        )(input)
+    }
+
+    /// Left associative chain of `operand (operator operand)*`, the operators
+    /// being some of `operators`
+    fn parse_chain<'a>(
+        input: &'a str,
+        operators: &'static str,
+        operand: fn(&str) -> IResult<&str, Expression>,
+    ) -> IResult<&'a str, Expression> {
+        let (input, first) = operand(input)?;
+        let (input, rest) = many0(pair(one_of(operators), operand))(input)?;
+        let expression = rest.into_iter().fold(first, |lhs, (operator, rhs)| {
+            let operator = Operator::new(operator).expect("parsed operators are supported");
+            Expression::binary(operator, lhs, rhs)
+        });
+        Ok((input, expression))
+    }
+
+    fn parse_product(input: &str) -> IResult<&str, Expression> {
+        Expression::parse_chain(input, "*/", Expression::parse_factor)
+    }
+
+    fn parse_sum(input: &str) -> IResult<&str, Expression> {
+        Expression::parse_chain(input, "+-", Expression::parse_product)
+    }
+
+    fn fmt_operand(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        parent: Operator,
+        right: bool,
+    ) -> std::fmt::Result {
+        // Parentheses only where dropping them changes the meaning
+        let needed = match self {
+            Expression::Value(_) => false,
+            Expression::Binary(operator, _, _) => {
+                // `a - (b - c)` or `a * (b / c)` but not `a + (b + c)`
+                let associative =
+                    *operator == parent && matches!(parent, Operator::Add | Operator::Multiply);
+                operator.precedence() < parent.precedence()
+                    || (right && operator.precedence() == parent.precedence() && !associative)
+            }
         };
-        result.unwrap_or_else(|| panic!("operation {:?} cannot be applied to\nrhs:{}", self, other))
+        match needed {
+            true => write!(f, "({self})"),
+            false => write!(f, "{self}"),
+        }
     }
+}
 
-    pub fn value(&self) -> Value {
+impl std::fmt::Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Operation::Multiply(v) => *v,
-            Operation::Divide(v) => *v,
-            Operation::Add(v) => *v,
-            Operation::Subtract(v) => *v,
+            Expression::Value(value) => write!(f, "{value}"),
+            Expression::Binary(operator, lhs, rhs) => {
+                lhs.fmt_operand(f, *operator, false)?;
+                write!(f, " {} ", operator.symbol())?;
+                rhs.fmt_operand(f, *operator, true)
+            }
         }
     }
 }
 
+/// How a monkey changes the worry level of an item it inspects
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Operation {
+    expression: Expression,
+}
+
+impl Operation {
+    /// `old <operator> value`, the shape of every puzzle operation
+    pub fn new(operator: char, value: Value) -> Result<Self, OperationError> {
+        let operator = Operator::new(operator)?;
+        if operator == Operator::Divide && value == Value::Const(0) {
+            return Err(OperationError::ZeroDivision);
+        }
+        Ok(Self {
+            expression: Expression::binary(
+                operator,
+                Expression::Value(Value::Old),
+                Expression::Value(value),
+            ),
+        })
+    }
+
+    pub fn expression(&self) -> &Expression {
+        &self.expression
+    }
+
+    /// New worry level of `old`, an error instead of an overflow, an
+    /// underflow or a zero division
+    pub fn evaluate(&self, old: Item) -> Result<WorryLevel, OperationError> {
+        self.evaluate_worry(&WorryLevel::from(old), &())
+    }
+
+    /// New worry level of `old` computed with any [`Worry`] backend, an
+    /// error for the operators it doesn't support
+    pub fn evaluate_worry<W: Worry>(
+        &self,
+        old: &W,
+        context: &W::Context,
+    ) -> Result<W, OperationError> {
+        self.expression.evaluate(old, context)
+    }
+
+    /// Parses the expression after `new = `, spaces around operands and
+    /// parentheses included
+    pub fn parse_operation(input: &str) -> IResult<&str, Operation> {
+        map(Expression::parse_sum, |expression| Operation { expression })(input)
+    }
+}
+
 impl std::str::FromStr for Operation {
     type Err = OperationError;
 
-    fn from_str(_s: &str) -> Result<Self, Self::Err> {
-        todo!()
+    /// Either the whole `new = ...` or only the expression
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        all_consuming(preceded(
+            opt(pair(space0, tag("new = "))),
+            Operation::parse_operation,
+        ))(s)
+        .finish()
+        .map(|(_, operation)| operation)
+        .map_err(|_| OperationError::ParsingFailed(s.to_owned()))
     }
 }
 
 impl std::fmt::Display for Operation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Operation::Multiply(v) => write!(f, "multiplied by {}", v),
-            Operation::Divide(v) => write!(f, "divided by {}", v),
-            Operation::Add(v) => write!(f, "increases by {}", v),
-            Operation::Subtract(v) => write!(f, "subtracted by {}", v),
+        write!(f, "new = {}", self.expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn evaluate(operation: &str, old: WorryLevel) -> Result<WorryLevel, OperationError> {
+        operation
+            .parse::<Operation>()
+            .unwrap()
+            .evaluate(Item::from(old))
+    }
+
+    #[test]
+    fn puzzle_operations() {
+        assert_eq!(
+            "new = old * 19".parse::<Operation>().unwrap(),
+            Operation::new('*', Value::Const(19)).unwrap()
+        );
+        assert_eq!(evaluate("old * old", 7), Ok(49));
+        assert_eq!(evaluate("old + 6", 7), Ok(13));
+        // old comes first
+        assert_eq!(evaluate("old - 6", 7), Ok(1));
+        assert_eq!(evaluate("old / 2", 7), Ok(3));
+    }
+
+    #[test]
+    fn precedence_and_parentheses() {
+        assert_eq!(evaluate("old + 2 * 3", 1), Ok(7));
+        assert_eq!(evaluate("(old + 2) * 3", 1), Ok(9));
+        assert_eq!(evaluate("old - 2 - 3", 10), Ok(5));
+        assert_eq!(evaluate("old - (3 - 2 + 1)", 10), Ok(8));
+        assert_eq!(evaluate("100 / old / 5", 2), Ok(10));
+        assert_eq!(evaluate("( ( old ) )", 4), Ok(4));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for text in [
+            "new = old * 19",
+            "new = (old + 2) * 3",
+            "new = old - (2 - 3)",
+            "new = old - 2 - 3",
+            "new = old * old + 1",
+            "new = 100 / (old / 5)",
+            "new = old * (old / 5)",
+            "new = old + old + 1",
+        ] {
+            let operation = text.parse::<Operation>().unwrap();
+            assert_eq!(operation.to_string(), text);
         }
     }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            evaluate("old / (old - 3)", 3),
+            Err(OperationError::ZeroDivision)
+        );
+        assert_eq!(
+            evaluate("old - 4", 3),
+            Err(OperationError::OutOfRange {
+                expression: "old - 4".to_owned(),
+                old: "3".to_owned()
+            })
+        );
+        assert!(evaluate("old * old", u64::MAX).is_err());
+        assert_eq!(
+            "old % 2".parse::<Operation>(),
+            Err(OperationError::ParsingFailed("old % 2".to_owned()))
+        );
+        assert!("(old + 2".parse::<Operation>().is_err());
+        assert_eq!(
+            Operation::new('/', 0.into()),
+            Err(OperationError::ZeroDivision)
+        );
+        assert_eq!(
+            Operation::new('^', 0.into()),
+            Err(OperationError::Unsupported('^'))
+        );
+    }
 }
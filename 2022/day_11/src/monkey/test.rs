@@ -1,41 +1,125 @@
 use log::debug;
+use nom::{branch::alt, bytes::complete::tag, combinator::map, sequence::preceded, IResult};
 
-use super::{operation::Operation, WorryLevel};
+use super::WorryLevel;
+
+/// What a monkey checks on the worry level before throwing an item
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Predicate {
+    DivisibleBy(WorryLevel),
+    GreaterThan(WorryLevel),
+    LessThan(WorryLevel),
+    EqualTo(WorryLevel),
+}
+
+impl Predicate {
+    pub fn holds(&self, worry: WorryLevel) -> bool {
+        match *self {
+            // Nothing is divisible by 0, rather than a panic
+            Predicate::DivisibleBy(divisor) => divisor != 0 && worry.is_multiple_of(divisor),
+            Predicate::GreaterThan(value) => worry > value,
+            Predicate::LessThan(value) => worry < value,
+            Predicate::EqualTo(value) => worry == value,
+        }
+    }
+
+    /// Divisor of a divisibility test
+    pub fn divisor(&self) -> Option<WorryLevel> {
+        match *self {
+            Predicate::DivisibleBy(divisor) => Some(divisor),
+            _ => None,
+        }
+    }
+
+    /// `divisible by N`, `greater than N`, `less than N` or `equal to N`
+    pub fn parse_predicate(input: &str) -> IResult<&str, Predicate> {
+        let number = nom::character::complete::u64;
+        alt((
+            map(
+                preceded(tag("divisible by "), number),
+                Predicate::DivisibleBy,
+            ),
+            map(
+                preceded(tag("greater than "), number),
+                Predicate::GreaterThan,
+            ),
+            map(preceded(tag("less than "), number), Predicate::LessThan),
+            map(preceded(tag("equal to "), number), Predicate::EqualTo),
+        ))(input)
+    }
+}
+
+impl std::fmt::Display for Predicate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Predicate::DivisibleBy(v) => write!(f, "divisible by {v}"),
+            Predicate::GreaterThan(v) => write!(f, "greater than {v}"),
+            Predicate::LessThan(v) => write!(f, "less than {v}"),
+            Predicate::EqualTo(v) => write!(f, "equal to {v}"),
+        }
+    }
+}
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct Test {
-    operation: Operation,
+    predicate: Predicate,
     if_true_throw_to: usize,
     if_false_throw_to: usize,
 }
 
 impl Test {
-    pub fn new(operation: Operation, if_true_throw_to: usize, if_false_throw_to: usize) -> Self {
+    pub fn new(predicate: Predicate, if_true_throw_to: usize, if_false_throw_to: usize) -> Self {
         Self {
-            operation,
+            predicate,
             if_true_throw_to,
             if_false_throw_to,
         }
     }
 
+    /// Monkey the item is thrown to
     pub fn apply(&self, current_worry: WorryLevel) -> usize {
+        let holds = self.predicate.holds(current_worry);
         debug!(
-            "    Current worry level {} is not divisible by {}",
+            "    Current worry level {} is {}{}",
             current_worry,
-            self.operation.value()
+            if holds { "" } else { "not " },
+            self.predicate
         );
-
-        if let crate::monkey::Value::Const(op_value) = self.operation.value() {
-            match current_worry % op_value {
-                0 => self.if_true_throw_to,
-                _ => self.if_false_throw_to,
-            }
-        } else {
-            panic!("wrong operation in test. should be only division");
+        match holds {
+            true => self.if_true_throw_to,
+            false => self.if_false_throw_to,
         }
     }
 
-    pub fn operation(&self) -> &Operation {
-        &self.operation
+    pub fn predicate(&self) -> &Predicate {
+        &self.predicate
+    }
+
+    /// Monkeys the item can be thrown to, when the test holds and when not
+    pub fn targets(&self) -> (usize, usize) {
+        (self.if_true_throw_to, self.if_false_throw_to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::Finish;
+
+    #[test]
+    fn predicates() {
+        let (_, predicate) = Predicate::parse_predicate("greater than 10")
+            .finish()
+            .unwrap();
+        assert_eq!(predicate, Predicate::GreaterThan(10));
+        let test = Test::new(predicate, 1, 2);
+        assert_eq!((test.apply(11), test.apply(10)), (1, 2));
+
+        assert!(Predicate::DivisibleBy(5).holds(15));
+        assert!(!Predicate::DivisibleBy(0).holds(15));
+        assert!(Predicate::LessThan(3).holds(2));
+        assert!(Predicate::EqualTo(3).holds(3));
+        assert_eq!(Predicate::EqualTo(3).divisor(), None);
+        assert!(Predicate::parse_predicate("odd").finish().is_err());
     }
 }
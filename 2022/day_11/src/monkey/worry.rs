@@ -0,0 +1,256 @@
+use num_bigint::BigUint;
+use num_traits::{CheckedSub, Zero};
+
+use super::{Monkey, MonkeyError, Operator, Predicate, WorryLevel};
+
+/// Number type worry levels are computed with during a game
+pub trait Worry: Clone + std::fmt::Debug + std::fmt::Display {
+    /// Name used in errors
+    const NAME: &'static str;
+
+    /// What every value of a game shares, like the modulus of residues
+    type Context;
+
+    fn context(monkeys: &[Monkey]) -> Result<Self::Context, MonkeyError>;
+
+    fn from_level(level: WorryLevel, context: &Self::Context) -> Self;
+
+    /// The value as a plain worry level, none if it doesn't fit
+    fn to_level(&self) -> Option<WorryLevel>;
+
+    fn is_zero(&self) -> bool;
+
+    /// Whether `apply` knows `operator` at all
+    fn supports(_operator: Operator) -> bool {
+        true
+    }
+
+    /// `self operator rhs`, none when out of range or dividing by zero
+    fn apply(&self, operator: Operator, rhs: &Self) -> Option<Self>;
+
+    /// Whether `predicate` holds, none if the value can't tell
+    fn test(&self, predicate: &Predicate) -> Option<bool>;
+}
+
+/// Plain `u64`, erroring on overflow
+impl Worry for WorryLevel {
+    const NAME: &'static str = "u64";
+
+    type Context = ();
+
+    fn context(_monkeys: &[Monkey]) -> Result<Self::Context, MonkeyError> {
+        Ok(())
+    }
+
+    fn from_level(level: WorryLevel, _context: &Self::Context) -> Self {
+        level
+    }
+
+    fn to_level(&self) -> Option<WorryLevel> {
+        Some(*self)
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == 0
+    }
+
+    fn apply(&self, operator: Operator, rhs: &Self) -> Option<Self> {
+        match operator {
+            Operator::Add => self.checked_add(*rhs),
+            Operator::Subtract => WorryLevel::checked_sub(*self, *rhs),
+            Operator::Multiply => self.checked_mul(*rhs),
+            Operator::Divide => self.checked_div(*rhs),
+        }
+    }
+
+    fn test(&self, predicate: &Predicate) -> Option<bool> {
+        Some(predicate.holds(*self))
+    }
+}
+
+/// Exact values, as big as they get
+impl Worry for BigUint {
+    const NAME: &'static str = "BigUint";
+
+    type Context = ();
+
+    fn context(_monkeys: &[Monkey]) -> Result<Self::Context, MonkeyError> {
+        Ok(())
+    }
+
+    fn from_level(level: WorryLevel, _context: &Self::Context) -> Self {
+        BigUint::from(level)
+    }
+
+    fn to_level(&self) -> Option<WorryLevel> {
+        self.try_into().ok()
+    }
+
+    fn is_zero(&self) -> bool {
+        Zero::is_zero(self)
+    }
+
+    fn apply(&self, operator: Operator, rhs: &Self) -> Option<Self> {
+        match operator {
+            Operator::Add => Some(self + rhs),
+            Operator::Subtract => self.checked_sub(rhs),
+            Operator::Multiply => Some(self * rhs),
+            Operator::Divide => (!Zero::is_zero(rhs)).then(|| self / rhs),
+        }
+    }
+
+    fn test(&self, predicate: &Predicate) -> Option<bool> {
+        let holds = match *predicate {
+            Predicate::DivisibleBy(divisor) => {
+                divisor != 0 && Zero::is_zero(&(self % BigUint::from(divisor)))
+            }
+            Predicate::GreaterThan(value) => *self > BigUint::from(value),
+            Predicate::LessThan(value) => *self < BigUint::from(value),
+            Predicate::EqualTo(value) => *self == BigUint::from(value),
+        };
+        Some(holds)
+    }
+}
+
+/// Worry level modulo the least common multiple of the test divisors.
+///
+/// Sums, differences and products of residues are residues of the results,
+/// and divisibility by any of the divisors is kept, so the game plays the
+/// same forever without the numbers growing. Divisions and comparisons can't
+/// be done on residues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Residue {
+    value: WorryLevel,
+    modulus: WorryLevel,
+}
+
+impl Residue {
+    pub fn value(&self) -> WorryLevel {
+        self.value
+    }
+
+    pub fn modulus(&self) -> WorryLevel {
+        self.modulus
+    }
+}
+
+impl std::fmt::Display for Residue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (mod {})", self.value, self.modulus)
+    }
+}
+
+fn gcd(a: WorryLevel, b: WorryLevel) -> WorryLevel {
+    match b {
+        0 => a,
+        b => gcd(b, a % b),
+    }
+}
+
+impl Worry for Residue {
+    const NAME: &'static str = "modular";
+
+    /// The modulus
+    type Context = WorryLevel;
+
+    /// Least common multiple of the divisors, every test has to be a
+    /// divisibility one
+    fn context(monkeys: &[Monkey]) -> Result<Self::Context, MonkeyError> {
+        monkeys.iter().try_fold(1, |lcm: WorryLevel, monkey| {
+            let predicate = monkey.test().predicate();
+            let divisor = predicate
+                .divisor()
+                .filter(|&divisor| divisor != 0)
+                .ok_or_else(|| MonkeyError::Unsupported {
+                    backend: Self::NAME,
+                    what: format!("test if {predicate}"),
+                })?;
+            (lcm / gcd(lcm, divisor))
+                .checked_mul(divisor)
+                .ok_or_else(|| MonkeyError::Unsupported {
+                    backend: Self::NAME,
+                    what: "use a modulus above 64 bits".to_owned(),
+                })
+        })
+    }
+
+    fn from_level(level: WorryLevel, context: &Self::Context) -> Self {
+        Self {
+            value: level % context,
+            modulus: *context,
+        }
+    }
+
+    fn to_level(&self) -> Option<WorryLevel> {
+        Some(self.value)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value == 0
+    }
+
+    fn supports(operator: Operator) -> bool {
+        operator != Operator::Divide
+    }
+
+    fn apply(&self, operator: Operator, rhs: &Self) -> Option<Self> {
+        let (a, b, m) = (
+            u128::from(self.value),
+            u128::from(rhs.value),
+            u128::from(self.modulus),
+        );
+        let value = match operator {
+            Operator::Add => (a + b) % m,
+            Operator::Subtract => (a + m - b) % m,
+            Operator::Multiply => a * b % m,
+            Operator::Divide => return None,
+        };
+        Some(Self {
+            value: value as WorryLevel,
+            modulus: self.modulus,
+        })
+    }
+
+    fn test(&self, predicate: &Predicate) -> Option<bool> {
+        predicate
+            .divisor()
+            .filter(|&divisor| divisor != 0 && self.modulus.is_multiple_of(divisor))
+            .map(|divisor| self.value.is_multiple_of(divisor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn residues() {
+        let a = Residue::from_level(100, &12);
+        let b = Residue::from_level(7, &12);
+        assert_eq!(a.value(), 4);
+        assert_eq!(a.apply(Operator::Add, &b).unwrap().value(), 11);
+        assert_eq!(a.apply(Operator::Subtract, &b).unwrap().value(), 9);
+        assert_eq!(a.apply(Operator::Multiply, &b).unwrap().value(), 4);
+        assert_eq!(a.apply(Operator::Divide, &b), None);
+        assert_eq!(a.test(&Predicate::DivisibleBy(4)), Some(true));
+        assert_eq!(a.test(&Predicate::DivisibleBy(5)), None);
+        assert_eq!(a.test(&Predicate::GreaterThan(5)), None);
+
+        let big = Residue::from_level(u64::MAX - 1, &(u64::MAX - 2));
+        assert_eq!(big.apply(Operator::Multiply, &big).unwrap().value(), 1);
+    }
+
+    #[test]
+    fn big_numbers() {
+        let a = BigUint::from(u64::MAX);
+        let square = a.apply(Operator::Multiply, &a).unwrap();
+        assert_eq!(square.to_level(), None);
+        assert_eq!(u64::MAX.apply(Operator::Multiply, &u64::MAX), None);
+        assert_eq!(
+            square.apply(Operator::Divide, &a).unwrap().to_level(),
+            Some(u64::MAX)
+        );
+        assert_eq!(a.apply(Operator::Divide, &BigUint::zero()), None);
+        assert_eq!(square.test(&Predicate::GreaterThan(u64::MAX)), Some(true));
+    }
+}
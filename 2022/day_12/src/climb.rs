@@ -0,0 +1,263 @@
+//! Breadth first searches over the heightmap
+
+use std::collections::VecDeque;
+
+use crate::grid::{Coord, Grid};
+
+/// How much higher or lower a step may go, `None` for no limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClimbRule {
+    pub max_ascent: Option<u8>,
+    pub max_descent: Option<u8>,
+}
+
+impl ClimbRule {
+    /// At most one higher, as low as wanted
+    pub const PUZZLE: Self = Self {
+        max_ascent: Some(1),
+        max_descent: None,
+    };
+
+    /// Whether a step from elevation `from` to `to` is allowed
+    pub fn allows(&self, from: u8, to: u8) -> bool {
+        let within = |limit: Option<u8>, diff: u8| limit.is_none_or(|limit| diff <= limit);
+        match to >= from {
+            true => within(self.max_ascent, to - from),
+            false => within(self.max_descent, from - to),
+        }
+    }
+}
+
+impl Default for ClimbRule {
+    fn default() -> Self {
+        Self::PUZZLE
+    }
+}
+
+/// Steps from the closest source to every cell, or from every cell to the
+/// closest target for a reverse search
+#[derive(Debug, Clone)]
+pub struct Distances<'g> {
+    grid: &'g Grid,
+    steps: Vec<Option<usize>>,
+    /// Cell the search reached each cell from
+    previous: Vec<Option<usize>>,
+    reversed: bool,
+}
+
+impl Distances<'_> {
+    /// Fewest steps between `coord` and the sources, none if it can't be
+    /// reached
+    pub fn steps(&self, coord: Coord) -> Option<usize> {
+        self.steps[self.grid.index(coord)?]
+    }
+
+    /// Cells which can be reached, with their steps
+    pub fn reached(&self) -> impl Iterator<Item = (Coord, usize)> + '_ {
+        self.steps
+            .iter()
+            .enumerate()
+            .filter_map(|(index, steps)| Some((self.grid.coord(index), (*steps)?)))
+    }
+
+    /// One of the shortest paths between `coord` and the sources, in walking
+    /// order: from a source to `coord`, or from `coord` to a target for a
+    /// reverse search
+    pub fn path(&self, coord: Coord) -> Option<Path> {
+        let mut index = self.grid.index(coord)?;
+        self.steps[index]?;
+        let mut coords = vec![coord];
+        while let Some(previous) = self.previous[index] {
+            coords.push(self.grid.coord(previous));
+            index = previous;
+        }
+        if !self.reversed {
+            coords.reverse();
+        }
+        Some(Path { coords })
+    }
+}
+
+/// Cells walked through, both ends included
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    coords: Vec<Coord>,
+}
+
+impl Path {
+    pub fn coords(&self) -> &[Coord] {
+        &self.coords
+    }
+
+    pub fn start(&self) -> Coord {
+        self.coords[0]
+    }
+
+    pub fn end(&self) -> Coord {
+        self.coords[self.coords.len() - 1]
+    }
+
+    pub fn steps(&self) -> usize {
+        self.coords.len() - 1
+    }
+
+    /// The grid with an arrow on every cell left, `E` on the last one and
+    /// `.` elsewhere, as drawn in the puzzle
+    pub fn render(&self, grid: &Grid) -> String {
+        let mut cells = vec![vec!['.'; grid.width()]; grid.height()];
+        for pair in self.coords.windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            cells[from.row][from.col] = match (to.row.cmp(&from.row), to.col.cmp(&from.col)) {
+                (std::cmp::Ordering::Less, _) => '^',
+                (std::cmp::Ordering::Greater, _) => 'v',
+                (_, std::cmp::Ordering::Less) => '<',
+                _ => '>',
+            };
+        }
+        let end = self.end();
+        cells[end.row][end.col] = 'E';
+        cells
+            .into_iter()
+            .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+            .collect()
+    }
+}
+
+// Search impls
+impl Grid {
+    /// Multi-source breadth first search, stepping from `from` to `to` when
+    /// `walkable(from, to)`
+    fn bfs(
+        &self,
+        sources: impl IntoIterator<Item = Coord>,
+        walkable: impl Fn(u8, u8) -> bool,
+        reversed: bool,
+    ) -> Distances<'_> {
+        let cells = self.width() * self.height();
+        let mut steps = vec![None; cells];
+        let mut previous = vec![None; cells];
+        let mut queue = VecDeque::new();
+        for index in sources.into_iter().filter_map(|coord| self.index(coord)) {
+            if steps[index].is_none() {
+                steps[index] = Some(0);
+                queue.push_back(index);
+            }
+        }
+
+        while let Some(current) = queue.pop_front() {
+            let coord = self.coord(current);
+            let elevation = self.elevation(coord).expect("queued cells are in the grid");
+            for neighbour in self.neighbours(coord) {
+                let next = self.index(neighbour).expect("neighbours are in the grid");
+                let next_elevation = self
+                    .elevation(neighbour)
+                    .expect("neighbours are in the grid");
+                if steps[next].is_none() && walkable(elevation, next_elevation) {
+                    steps[next] = steps[current].map(|steps| steps + 1);
+                    previous[next] = Some(current);
+                    queue.push_back(next);
+                }
+            }
+        }
+        Distances {
+            grid: self,
+            steps,
+            previous,
+            reversed,
+        }
+    }
+
+    /// Steps from the closest of `sources` to every cell
+    pub fn distances_from(
+        &self,
+        rule: ClimbRule,
+        sources: impl IntoIterator<Item = Coord>,
+    ) -> Distances<'_> {
+        self.bfs(sources, |from, to| rule.allows(from, to), false)
+    }
+
+    /// Steps from every cell to the closest of `targets`, searched backwards
+    /// from them
+    pub fn distances_to(
+        &self,
+        rule: ClimbRule,
+        targets: impl IntoIterator<Item = Coord>,
+    ) -> Distances<'_> {
+        self.bfs(targets, |to, from| rule.allows(from, to), true)
+    }
+
+    /// Shortest path from `S` to `E`
+    pub fn shortest_path(&self, rule: ClimbRule) -> Option<Path> {
+        self.distances_from(rule, [self.start()]).path(self.end())
+    }
+
+    /// Shortest path to `E` from any cell of elevation `elevation`
+    pub fn shortest_from_elevation(&self, rule: ClimbRule, elevation: u8) -> Option<Path> {
+        let distances = self.distances_to(rule, [self.end()]);
+        self.cells_at(elevation)
+            .filter_map(|coord| Some((distances.steps(coord)?, coord)))
+            .min()
+            .and_then(|(_, coord)| distances.path(coord))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::LOWEST;
+
+    const INPUT: &str = "Sabqponm\nabcryxxl\naccszExk\nacctuvwj\nabdefghi";
+
+    #[test]
+    fn climb_rules() {
+        assert!(ClimbRule::PUZZLE.allows(3, 4));
+        assert!(!ClimbRule::PUZZLE.allows(3, 5));
+        assert!(ClimbRule::PUZZLE.allows(25, 0));
+        let gentle = ClimbRule {
+            max_ascent: Some(2),
+            max_descent: Some(1),
+        };
+        assert!(gentle.allows(3, 5) && gentle.allows(3, 2) && !gentle.allows(3, 1));
+    }
+
+    #[test]
+    fn paths() {
+        let grid = INPUT.parse::<Grid>().unwrap();
+        let path = grid.shortest_path(ClimbRule::PUZZLE).unwrap();
+        assert_eq!(path.steps(), 31);
+        assert_eq!((path.start(), path.end()), (grid.start(), grid.end()));
+        assert!(path.coords().windows(2).all(|pair| {
+            let (from, to) = (grid.elevation(pair[0]), grid.elevation(pair[1]));
+            ClimbRule::PUZZLE.allows(from.unwrap(), to.unwrap())
+        }));
+        assert_eq!(path.render(&grid).lines().count(), 5);
+
+        let path = grid
+            .shortest_from_elevation(ClimbRule::PUZZLE, LOWEST)
+            .unwrap();
+        assert_eq!(path.steps(), 29);
+        assert_eq!(path.end(), grid.end());
+        assert_eq!(grid.elevation(path.start()), Some(LOWEST));
+
+        let reverse = grid.distances_to(ClimbRule::PUZZLE, [grid.end()]);
+        assert_eq!(reverse.steps(grid.start()), Some(31));
+        assert_eq!(reverse.path(grid.start()).unwrap().steps(), 31);
+    }
+
+    #[test]
+    fn descents() {
+        // c to a is two steps down, which the puzzle allows
+        let grid = "Sbca\nzzzE".parse::<Grid>().unwrap();
+        let low = Coord::from((0, 3));
+        let distances = grid.distances_from(ClimbRule::PUZZLE, [grid.start()]);
+        assert_eq!(distances.steps(low), Some(3));
+        assert_eq!(distances.path(low).unwrap().render(&grid), ">>>E\n....\n");
+        let careful = ClimbRule {
+            max_descent: Some(1),
+            ..ClimbRule::PUZZLE
+        };
+        let distances = grid.distances_from(careful, [grid.start()]);
+        assert_eq!(distances.steps(low), None);
+        assert_eq!(distances.reached().count(), 3);
+    }
+}
@@ -0,0 +1,256 @@
+//! Heightmap parsed from the puzzle input
+
+use aoc_common::prelude::{ParseError, ParseErrorKind};
+
+/// Elevation of `a` and of the start `S`
+pub const LOWEST: u8 = 0;
+/// Elevation of `z` and of the end `E`
+pub const HIGHEST: u8 = 25;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("the grid is empty")]
+    Empty,
+    #[error("the grid has no start `S`")]
+    MissingStart,
+    #[error("the grid has no end `E`")]
+    MissingEnd,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Coord {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl From<(usize, usize)> for Coord {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self { row, col }
+    }
+}
+
+impl std::fmt::Debug for Coord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("").field(&self.row).field(&self.col).finish()
+    }
+}
+
+/// Elevation of a letter, `a` being 0 and `z` 25
+pub fn elevation_of(c: char) -> Option<u8> {
+    match c {
+        'S' => Some(LOWEST),
+        'E' => Some(HIGHEST),
+        'a'..='z' => Some(c as u8 - b'a'),
+        _ => None,
+    }
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    /// Row after row
+    elevations: Vec<u8>,
+    start: Coord,
+    end: Coord,
+}
+
+impl Grid {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn start(&self) -> Coord {
+        self.start
+    }
+
+    pub fn end(&self) -> Coord {
+        self.end
+    }
+
+    pub fn in_bounds(&self, coord: Coord) -> bool {
+        coord.row < self.height && coord.col < self.width
+    }
+
+    pub fn elevation(&self, coord: Coord) -> Option<u8> {
+        self.index(coord).map(|index| self.elevations[index])
+    }
+
+    pub(crate) fn index(&self, coord: Coord) -> Option<usize> {
+        self.in_bounds(coord)
+            .then_some(coord.row * self.width + coord.col)
+    }
+
+    pub(crate) fn coord(&self, index: usize) -> Coord {
+        Coord {
+            row: index / self.width,
+            col: index % self.width,
+        }
+    }
+
+    /// Every cell, row after row
+    pub fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        (0..self.elevations.len()).map(|index| self.coord(index))
+    }
+
+    /// Cells of elevation `elevation`, `S` and `E` included
+    pub fn cells_at(&self, elevation: u8) -> impl Iterator<Item = Coord> + '_ {
+        self.coords()
+            .filter(move |&coord| self.elevation(coord) == Some(elevation))
+    }
+
+    /// Cells up, down, left and right of `coord` which are in the grid
+    pub fn neighbours(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .into_iter()
+            .filter_map(move |(dr, dc)| {
+                Some(Coord {
+                    row: coord.row.checked_add_signed(dr)?,
+                    col: coord.col.checked_add_signed(dc)?,
+                })
+            })
+            .filter(|&c| self.in_bounds(c))
+    }
+}
+
+impl std::fmt::Display for Grid {
+    /// The heightmap as in the puzzle input
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let c = match Coord::from((row, col)) {
+                    coord if coord == self.start => 'S',
+                    coord if coord == self.end => 'E',
+                    _ => (b'a' + self.elevations[row * self.width + col]) as char,
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f)?;
+        writeln!(f, "start: {:?}, end: {:?}", self.start, self.end)?;
+        write!(f, "{self}")
+    }
+}
+
+impl std::str::FromStr for Grid {
+    type Err = GridError;
+
+    /// Rows of `a` to `z` of the same length, with exactly one `S` and one `E`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut start, mut end) = (None, None);
+        let mut width = None;
+        let mut elevations = Vec::new();
+        let lines = s.lines().collect::<Vec<_>>();
+        for (row, line) in lines.iter().enumerate() {
+            let error = |col: usize, kind| ParseError::new(row + 1, col + 1, kind);
+            let mut cols = 0;
+            for (col, c) in line.chars().enumerate() {
+                let coord = Coord { row, col };
+                let mark = match c {
+                    'S' => Some((&mut start, "start `S`")),
+                    'E' => Some((&mut end, "end `E`")),
+                    _ => None,
+                };
+                if let Some((mark, name)) = mark {
+                    if mark.replace(coord).is_some() {
+                        let kind = ParseErrorKind::Invalid(format!("more than one {name}"));
+                        return Err(error(col, kind).into());
+                    }
+                }
+                let elevation = elevation_of(c).ok_or_else(|| {
+                    error(
+                        col,
+                        ParseErrorKind::Expected("`a` to `z`, `S` or `E`".to_owned()),
+                    )
+                })?;
+                elevations.push(elevation);
+                cols += 1;
+            }
+            match width {
+                None => width = Some(cols),
+                Some(width) if width != cols => {
+                    let kind = ParseErrorKind::Expected(format!("{width} columns"));
+                    return Err(error(cols.min(width), kind).into());
+                }
+                Some(_) => {}
+            }
+        }
+
+        let width = match width {
+            None | Some(0) => return Err(GridError::Empty),
+            Some(width) => width,
+        };
+        Ok(Self {
+            width,
+            height: lines.len(),
+            elevations,
+            start: start.ok_or(GridError::MissingStart)?,
+            end: end.ok_or(GridError::MissingEnd)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_grids() {
+        let parse = |s: &str| s.parse::<Grid>();
+        assert_eq!(parse(""), Err(GridError::Empty));
+        assert_eq!(parse("abc\nzyx"), Err(GridError::MissingStart));
+        assert_eq!(parse("Sbc\nzyx"), Err(GridError::MissingEnd));
+        assert_eq!(
+            parse("SbE\nzy"),
+            Err(ParseError::new(2, 3, ParseErrorKind::Expected("3 columns".to_owned())).into())
+        );
+        assert_eq!(
+            parse("SbE\nzS"),
+            Err(ParseError::new(
+                2,
+                2,
+                ParseErrorKind::Invalid("more than one start `S`".to_owned())
+            )
+            .into())
+        );
+        assert!(matches!(
+            parse("Sb1E"),
+            Err(GridError::Parse(ParseError {
+                line: 1,
+                column: 3,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn cells() {
+        let grid = "Sab\nazE".parse::<Grid>().unwrap();
+        assert_eq!(grid.to_string(), "Sab\nazE\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.end(), Coord::from((1, 2)));
+        assert_eq!(grid.elevation(grid.end()), Some(HIGHEST));
+        assert_eq!(grid.elevation((2, 0).into()), None);
+        assert_eq!(
+            grid.cells_at(LOWEST).collect::<Vec<_>>(),
+            [(0, 0).into(), (0, 1).into(), (1, 0).into()]
+        );
+        assert_eq!(
+            grid.neighbours((0, 0).into()).collect::<Vec<_>>(),
+            [(1, 0).into(), (0, 1).into()]
+        );
+    }
+}
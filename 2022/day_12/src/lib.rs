@@ -0,0 +1,40 @@
+pub mod climb;
+pub mod grid;
+
+use crate::{
+    climb::ClimbRule,
+    grid::{Grid, GridError, LOWEST},
+};
+
+/// Fewest steps from `S` to `E`, none if `E` can't be reached
+pub fn part_1(input: &str) -> Result<Option<usize>, GridError> {
+    let grid = input.parse::<Grid>()?;
+    Ok(grid
+        .shortest_path(ClimbRule::PUZZLE)
+        .map(|path| path.steps()))
+}
+
+/// Fewest steps to `E` from any cell of elevation `a`
+pub fn part_2(input: &str) -> Result<Option<usize>, GridError> {
+    let grid = input.parse::<Grid>()?;
+    Ok(grid
+        .shortest_from_elevation(ClimbRule::PUZZLE, LOWEST)
+        .map(|path| path.steps()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_macros::examples! {
+        part1: |input| part_1(input).unwrap().unwrap(),
+        part2: |input| part_2(input).unwrap().unwrap(),
+    }
+
+    #[test]
+    fn answers() {
+        let input = include_str!("../input.txt");
+        assert_eq!(part_1(input), Ok(Some(423)));
+        assert_eq!(part_2(input), Ok(Some(416)));
+    }
+}
@@ -0,0 +1,586 @@
+//! Data driven engine for cyclic dominance games.
+//!
+//! Shapes of a game are numbered `0..n`, `n` being odd. Shape `a` beats shape
+//! `b` when `(a - b) mod n` falls in the first half of `1..n`, so every shape
+//! beats exactly half of the others. With the shapes in the right order this
+//! gives rock-paper-scissors, rock-paper-scissors-lizard-Spock or any larger
+//! balanced game.
+
+use aoc_common::prelude::*;
+
+use crate::RoundResult;
+
+pub type ShapeId = usize;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum GameError {
+    #[error("a cyclic game needs an odd number of shapes, 3 to 25, got {0}")]
+    Size(usize),
+    #[error("expected a score for each of the {expected} shapes, got {got}")]
+    ShapeScores { expected: usize, got: usize },
+}
+
+/// Outcome of `you` against `opponent` in a game of `size` shapes
+pub fn cyclic_outcome(size: usize, you: ShapeId, opponent: ShapeId) -> RoundResult {
+    match (you + size - opponent) % size {
+        0 => RoundResult::Draw,
+        diff if diff <= size / 2 => RoundResult::Win,
+        _ => RoundResult::Loss,
+    }
+}
+
+/// Points given for the shape played and for the outcome of a round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scoring {
+    pub shapes: Vec<u32>,
+    pub loss: u32,
+    pub draw: u32,
+    pub win: u32,
+}
+
+impl Scoring {
+    /// The puzzle's scoring: shape `i` is worth `i + 1`, and a loss, draw and
+    /// win are worth 0, 3 and 6
+    pub fn standard(size: usize) -> Self {
+        Self {
+            shapes: (1..=size as u32).collect(),
+            loss: RoundResult::Loss.value() as u32,
+            draw: RoundResult::Draw.value() as u32,
+            win: RoundResult::Win.value() as u32,
+        }
+    }
+
+    pub fn outcome(&self, outcome: &RoundResult) -> u32 {
+        match outcome {
+            RoundResult::Loss => self.loss,
+            RoundResult::Draw => self.draw,
+            RoundResult::Win => self.win,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    names: Vec<String>,
+    scoring: Scoring,
+}
+
+impl Game {
+    /// Game with the given shape names, in dominance order, and the standard
+    /// scoring
+    pub fn new<S: Into<String>>(names: impl IntoIterator<Item = S>) -> Result<Self, GameError> {
+        let names = names.into_iter().map(Into::into).collect::<Vec<String>>();
+        let size = names.len();
+        if !(3..=25).contains(&size) || size % 2 == 0 {
+            return Err(GameError::Size(size));
+        }
+        Ok(Self {
+            names,
+            scoring: Scoring::standard(size),
+        })
+    }
+
+    /// Game of `size` shapes named by their number
+    pub fn cyclic(size: usize) -> Result<Self, GameError> {
+        Self::new((1..=size).map(|shape| shape.to_string()))
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Self::new(["Rock", "Paper", "Scissors"]).expect("3 is a valid game size")
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Self::new(["Rock", "Spock", "Paper", "Lizard", "Scissors"]).expect("5 is a valid game size")
+    }
+
+    pub fn with_scoring(mut self, scoring: Scoring) -> Result<Self, GameError> {
+        if scoring.shapes.len() != self.size() {
+            return Err(GameError::ShapeScores {
+                expected: self.size(),
+                got: scoring.shapes.len(),
+            });
+        }
+        self.scoring = scoring;
+        Ok(self)
+    }
+
+    pub fn size(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, shape: ShapeId) -> &str {
+        &self.names[shape]
+    }
+
+    pub fn shape(&self, name: &str) -> Option<ShapeId> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn scoring(&self) -> &Scoring {
+        &self.scoring
+    }
+
+    pub fn outcome(&self, you: ShapeId, opponent: ShapeId) -> RoundResult {
+        cyclic_outcome(self.size(), you, opponent)
+    }
+
+    /// Points for playing `you` against `opponent`
+    pub fn score(&self, you: ShapeId, opponent: ShapeId) -> u32 {
+        self.scoring.shapes[you] + self.scoring.outcome(&self.outcome(you, opponent))
+    }
+
+    /// Shape to play against `opponent` to get `outcome`.
+    ///
+    /// In games with more than 3 shapes several shapes give the same outcome,
+    /// the one scoring the most is picked (the first one on a tie).
+    pub fn response(&self, opponent: ShapeId, outcome: &RoundResult) -> ShapeId {
+        (0..self.size())
+            .filter(|&you| self.outcome(you, opponent) == *outcome)
+            .rev()
+            .max_by_key(|&you| self.scoring.shapes[you])
+            .expect("every outcome is reachable in a cyclic game")
+    }
+
+    /// Letters of the opponent column: `A`, `B`, ... one per shape
+    pub fn opponent_symbols(&self) -> Vec<char> {
+        ('A'..='Z').take(self.size()).collect()
+    }
+}
+
+/// How the second column of the strategy guide is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpretation {
+    /// The column is the shape to play
+    Shape,
+    /// The column is the outcome the round must have
+    Outcome,
+}
+
+impl Interpretation {
+    /// Letters of the second column. They end at `Z`, so `X`, `Y`, `Z` for
+    /// three shapes or outcomes, `V` to `Z` for five shapes...
+    pub fn symbols(&self, game: &Game) -> Vec<char> {
+        let count = match self {
+            Interpretation::Shape => game.size(),
+            Interpretation::Outcome => 3,
+        };
+        ('A'..='Z').skip(26 - count).collect()
+    }
+
+    /// Mapping of the second column the puzzle assumes
+    pub fn default_mapping(&self, game: &Game) -> Vec<Response> {
+        match self {
+            Interpretation::Shape => (0..game.size()).map(Response::Shape).collect(),
+            Interpretation::Outcome => [RoundResult::Loss, RoundResult::Draw, RoundResult::Win]
+                .into_iter()
+                .map(Response::Outcome)
+                .collect(),
+        }
+    }
+}
+
+/// What a letter of the second column stands for
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response {
+    Shape(ShapeId),
+    Outcome(RoundResult),
+}
+
+impl Response {
+    /// Shape you end up playing against `opponent`
+    pub fn shape(&self, game: &Game, opponent: ShapeId) -> ShapeId {
+        match self {
+            Response::Shape(shape) => *shape,
+            Response::Outcome(outcome) => game.response(opponent, outcome),
+        }
+    }
+}
+
+/// Mapping of the second column together with the total score it gives
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoredMapping {
+    pub mapping: Vec<Response>,
+    pub score: u32,
+}
+
+/// Best and worst possible readings of a guide
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappingReport {
+    pub best: ScoredMapping,
+    pub worst: ScoredMapping,
+}
+
+/// Strategy guide, the rounds being kept as column indexes until a mapping
+/// gives them a meaning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guide {
+    interpretation: Interpretation,
+    rounds: Vec<(ShapeId, usize)>,
+}
+
+impl Guide {
+    pub fn parse(
+        game: &Game,
+        interpretation: Interpretation,
+        input: &str,
+    ) -> Result<Self, ParseError> {
+        let opponents = game.opponent_symbols();
+        let responses = interpretation.symbols(game);
+        let symbol = |line: &str, token: Option<&str>, symbols: &[char]| {
+            let list = symbols.iter().map(char::to_string).collect::<Vec<_>>();
+            let kind = ParseErrorKind::Expected(format!("one of {}", list.join(", ")));
+            let Some(token) = token else {
+                return Err(ParseError::at_offset(line, line.len(), kind));
+            };
+            let mut chars = token.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => symbols.iter().position(|&s| s == c),
+                _ => None,
+            }
+            .ok_or_else(|| ParseError::at(line, token, kind))
+        };
+
+        let rounds = lines_with(input, |line| {
+            let mut tokens = line.split_whitespace();
+            let opponent = symbol(line, tokens.next(), &opponents)?;
+            let response = symbol(line, tokens.next(), &responses)?;
+            match tokens.next() {
+                None => Ok((opponent, response)),
+                Some(extra) => Err(ParseError::at(
+                    line,
+                    extra,
+                    ParseErrorKind::Expected("end of line".to_owned()),
+                )),
+            }
+        })?;
+
+        Ok(Self {
+            interpretation,
+            rounds,
+        })
+    }
+
+    pub fn interpretation(&self) -> Interpretation {
+        self.interpretation
+    }
+
+    /// Rounds as `(opponent, you)` shapes, reading the guide with `mapping`
+    pub fn rounds<'a>(
+        &'a self,
+        game: &'a Game,
+        mapping: &'a [Response],
+    ) -> impl Iterator<Item = (ShapeId, ShapeId)> + 'a {
+        self.rounds
+            .iter()
+            .map(move |&(opponent, column)| (opponent, mapping[column].shape(game, opponent)))
+    }
+
+    /// Total score when following the guide as the puzzle reads it
+    pub fn score(&self, game: &Game) -> u32 {
+        self.score_with(game, &self.interpretation.default_mapping(game))
+    }
+
+    pub fn score_with(&self, game: &Game, mapping: &[Response]) -> u32 {
+        self.rounds(game, mapping)
+            .map(|(opponent, you)| game.score(you, opponent))
+            .sum()
+    }
+
+    /// Best and worst total score over every one-to-one mapping of the second
+    /// column. On ties the first mapping in lexicographic order wins.
+    ///
+    /// Rounds are counted per opponent and column once, so a mapping's score
+    /// is a sum over the columns and finding the best one is an assignment
+    /// problem, solved without trying every permutation.
+    pub fn mapping_range(&self, game: &Game) -> MappingReport {
+        let values = self.interpretation.default_mapping(game);
+        let mut counts = vec![vec![0i64; game.size()]; values.len()];
+        for &(opponent, column) in &self.rounds {
+            counts[column][opponent] += 1;
+        }
+        // Points of every round of a column when it stands for a value
+        let points = counts
+            .iter()
+            .map(|opponents| {
+                values
+                    .iter()
+                    .map(|value| {
+                        opponents
+                            .iter()
+                            .enumerate()
+                            .map(|(opponent, &count)| {
+                                let you = value.shape(game, opponent);
+                                count * i64::from(game.score(you, opponent))
+                            })
+                            .sum::<i64>()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let negated = points
+            .iter()
+            .map(|row| row.iter().map(|&p| -p).collect())
+            .collect::<Vec<_>>();
+
+        let scored = |order: Vec<usize>| ScoredMapping {
+            score: order
+                .iter()
+                .enumerate()
+                .map(|(column, &value)| points[column][value] as u32)
+                .sum(),
+            mapping: order.into_iter().map(|i| values[i].clone()).collect(),
+        };
+        MappingReport {
+            best: scored(first_cheapest_assignment(&negated)),
+            worst: scored(first_cheapest_assignment(&points)),
+        }
+    }
+}
+
+/// Cheapest way of giving each row of the square `cost` matrix its own
+/// column, as the column of each row, the lexicographically first one when
+/// several cost the same.
+///
+/// Rows are settled one at a time, each taking the smallest column which
+/// still allows the optimal total for the rows left.
+fn first_cheapest_assignment(cost: &[Vec<i64>]) -> Vec<usize> {
+    let mut target = cheapest_assignment(cost).0;
+    let mut columns = (0..cost.len()).collect::<Vec<_>>();
+    let mut order = Vec::with_capacity(cost.len());
+    for row in 0..cost.len() {
+        let (idx, column) = columns
+            .iter()
+            .copied()
+            .enumerate()
+            .find(|&(idx, column)| {
+                let mut left = columns.clone();
+                left.remove(idx);
+                let rest = cost[row + 1..]
+                    .iter()
+                    .map(|r| left.iter().map(|&c| r[c]).collect())
+                    .collect::<Vec<_>>();
+                cost[row][column] + cheapest_assignment(&rest).0 == target
+            })
+            .expect("some column reaches the optimum");
+        target -= cost[row][column];
+        columns.remove(idx);
+        order.push(column);
+    }
+    order
+}
+
+/// Hungarian algorithm on a square `cost` matrix, returning the smallest
+/// total and the column of each row, in `O(n³)`
+fn cheapest_assignment(cost: &[Vec<i64>]) -> (i64, Vec<usize>) {
+    let n = cost.len();
+    // 1-based potentials and matching, column 0 being a sentinel
+    let mut row_potential = vec![0; n + 1];
+    let mut column_potential = vec![0; n + 1];
+    let mut row_of = vec![0; n + 1];
+    let mut previous = vec![0; n + 1];
+    for row in 1..=n {
+        row_of[0] = row;
+        let mut column = 0;
+        let mut slack = vec![i64::MAX; n + 1];
+        let mut used = vec![false; n + 1];
+        loop {
+            used[column] = true;
+            let current = row_of[column];
+            let mut delta = i64::MAX;
+            let mut next = 0;
+            for c in 1..=n {
+                if used[c] {
+                    continue;
+                }
+                let reduced =
+                    cost[current - 1][c - 1] - row_potential[current] - column_potential[c];
+                if reduced < slack[c] {
+                    slack[c] = reduced;
+                    previous[c] = column;
+                }
+                if slack[c] < delta {
+                    delta = slack[c];
+                    next = c;
+                }
+            }
+            for c in 0..=n {
+                if used[c] {
+                    row_potential[row_of[c]] += delta;
+                    column_potential[c] -= delta;
+                } else {
+                    slack[c] -= delta;
+                }
+            }
+            column = next;
+            if row_of[column] == 0 {
+                break;
+            }
+        }
+        // Flip the augmenting path back to the sentinel
+        while column != 0 {
+            let before = previous[column];
+            row_of[column] = row_of[before];
+            column = before;
+        }
+    }
+
+    let mut assignment = vec![0; n];
+    for column in 1..=n {
+        assignment[row_of[column] - 1] = column - 1;
+    }
+    let total = assignment
+        .iter()
+        .enumerate()
+        .map(|(row, &column)| cost[row][column])
+        .sum();
+    (total, assignment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUIDE: &str = "A Y\nB X\nC Z\n";
+
+    #[test]
+    fn rock_paper_scissors_rules() {
+        let game = Game::rock_paper_scissors();
+        let [rock, paper, scissors] = ["Rock", "Paper", "Scissors"].map(|n| game.shape(n).unwrap());
+        assert_eq!(game.outcome(paper, rock), RoundResult::Win);
+        assert_eq!(game.outcome(rock, scissors), RoundResult::Win);
+        assert_eq!(game.outcome(scissors, rock), RoundResult::Loss);
+        assert_eq!(game.outcome(paper, paper), RoundResult::Draw);
+        assert_eq!(game.response(rock, &RoundResult::Loss), scissors);
+    }
+
+    #[test]
+    fn lizard_spock_rules() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let shape = |name| game.shape(name).unwrap();
+        let wins = [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ];
+        for (winner, loser) in wins {
+            assert_eq!(game.outcome(shape(winner), shape(loser)), RoundResult::Win);
+            assert_eq!(game.outcome(shape(loser), shape(winner)), RoundResult::Loss);
+        }
+    }
+
+    #[test]
+    fn balanced_games() {
+        assert_eq!(Game::cyclic(4), Err(GameError::Size(4)));
+        assert_eq!(Game::cyclic(1), Err(GameError::Size(1)));
+        let game = Game::cyclic(7).unwrap();
+        for you in 0..7 {
+            let wins = (0..7)
+                .filter(|&op| game.outcome(you, op) == RoundResult::Win)
+                .count();
+            assert_eq!(wins, 3);
+        }
+    }
+
+    #[test]
+    fn both_interpretations() {
+        let game = Game::rock_paper_scissors();
+        let guide = Guide::parse(&game, Interpretation::Shape, GUIDE).unwrap();
+        assert_eq!(guide.score(&game), 15);
+        let guide = Guide::parse(&game, Interpretation::Outcome, GUIDE).unwrap();
+        assert_eq!(guide.score(&game), 12);
+    }
+
+    #[test]
+    fn custom_scoring() {
+        let scoring = Scoring {
+            shapes: vec![0, 0, 0],
+            loss: 0,
+            draw: 1,
+            win: 2,
+        };
+        let game = Game::rock_paper_scissors().with_scoring(scoring).unwrap();
+        let guide = Guide::parse(&game, Interpretation::Shape, GUIDE).unwrap();
+        // Win, loss, draw
+        assert_eq!(guide.score(&game), 3);
+
+        let scoring = Scoring::standard(5);
+        assert_eq!(
+            Game::rock_paper_scissors().with_scoring(scoring),
+            Err(GameError::ShapeScores {
+                expected: 3,
+                got: 5
+            })
+        );
+    }
+
+    #[test]
+    fn best_and_worst_mapping() {
+        let game = Game::rock_paper_scissors();
+        let guide = Guide::parse(&game, Interpretation::Shape, GUIDE).unwrap();
+        let report = guide.mapping_range(&game);
+        // Y -> Paper beats A, X -> Scissors beats B, Z -> Rock beats C
+        assert_eq!(report.best.score, 8 + 9 + 7);
+        assert_eq!(
+            report.best.mapping,
+            [Response::Shape(2), Response::Shape(1), Response::Shape(0)]
+        );
+        // Y -> Scissors, X -> Rock, Z -> Paper lose every round
+        assert_eq!(report.worst.score, 3 + 1 + 2);
+    }
+
+    #[test]
+    fn five_shape_guide() {
+        let game = Game::rock_paper_scissors_lizard_spock();
+        let guide = Guide::parse(&game, Interpretation::Shape, "E V\nA Z\n").unwrap();
+        // Rock beats Scissors, then Scissors lose to Rock
+        assert_eq!(guide.score(&game), 1 + 6 + 5);
+        // V -> Spock beats Scissors, Z -> Paper beats Rock
+        assert_eq!(guide.mapping_range(&game).best.score, 2 + 6 + 3 + 6);
+    }
+
+    #[test]
+    fn parse_errors_have_lines() {
+        let game = Game::rock_paper_scissors();
+        let error = Guide::parse(&game, Interpretation::Shape, "A Y\nB W\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        let error = Guide::parse(&game, Interpretation::Shape, "A Y\nB X\nD Z").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        let error = Guide::parse(&game, Interpretation::Outcome, "A").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 2));
+        let error = Guide::parse(&game, Interpretation::Outcome, "A  X Y").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 6));
+    }
+
+    #[test]
+    fn assignments() {
+        let cost = vec![vec![4, 1, 3], vec![2, 0, 5], vec![3, 2, 2]];
+        assert_eq!(cheapest_assignment(&cost), (5, vec![1, 0, 2]));
+        // Both diagonals cost 2, the first one in order is kept
+        let cost = vec![vec![1, 1], vec![1, 1]];
+        assert_eq!(first_cheapest_assignment(&cost), [0, 1]);
+        assert_eq!(cheapest_assignment(&[]), (0, vec![]));
+    }
+
+    #[test]
+    fn nine_shape_mappings() {
+        let game = Game::cyclic(9).unwrap();
+        // Opponent `i` always meets column `i`, several times over
+        let guide = "A R\nB S\nC T\nD U\nE V\nF W\nG X\nH Y\nI Z\n".repeat(50);
+        let guide = Guide::parse(&game, Interpretation::Shape, &guide).unwrap();
+        let report = guide.mapping_range(&game);
+        // Shape `i + 1` beats shape `i`, so every round can be won
+        assert_eq!(report.best.score, 50 * (9 * 6 + 45));
+        assert_eq!(
+            report.best.mapping,
+            (1..9).chain([0]).map(Response::Shape).collect::<Vec<_>>()
+        );
+        assert_eq!(report.worst.score, 50 * 45);
+    }
+}
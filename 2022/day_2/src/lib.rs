@@ -1,5 +1,10 @@
 use std::str::FromStr;
 
+use aoc_common::prelude::ParseError;
+use game::{cyclic_outcome, Game, Guide, Interpretation};
+
+pub mod game;
+
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub enum RoundResult {
     Loss = 0,
@@ -15,19 +20,7 @@ impl RoundResult {
         }
     }
     pub fn get_shape_for_opponent(&self, op: &Shape) -> Shape {
-        match self {
-            RoundResult::Loss => match op {
-                Shape::Rock => Shape::Scissors,
-                Shape::Paper => Shape::Rock,
-                Shape::Scissors => Shape::Paper,
-            },
-            RoundResult::Draw => op.clone(),
-            RoundResult::Win => match op {
-                Shape::Rock => Shape::Paper,
-                Shape::Paper => Shape::Scissors,
-                Shape::Scissors => Shape::Rock,
-            },
-        }
+        Shape::from_index(Game::rock_paper_scissors().response(op.index(), self))
     }
 }
 impl std::fmt::Display for RoundResult {
@@ -68,22 +61,17 @@ impl Shape {
         }
     }
     pub fn get_round_result(&self, op: &Self) -> RoundResult {
-        match self {
-            Shape::Rock => match op {
-                Shape::Rock => RoundResult::Draw,
-                Shape::Paper => RoundResult::Loss,
-                Shape::Scissors => RoundResult::Win,
-            },
-            Shape::Paper => match op {
-                Shape::Rock => RoundResult::Win,
-                Shape::Paper => RoundResult::Draw,
-                Shape::Scissors => RoundResult::Loss,
-            },
-            Shape::Scissors => match op {
-                Shape::Rock => RoundResult::Loss,
-                Shape::Paper => RoundResult::Win,
-                Shape::Scissors => RoundResult::Draw,
-            },
+        cyclic_outcome(3, self.index(), op.index())
+    }
+    /// Position of the shape in [`Game::rock_paper_scissors`]
+    pub fn index(&self) -> usize {
+        self.value() as usize - 1
+    }
+    fn from_index(index: usize) -> Self {
+        match index {
+            0 => Shape::Rock,
+            1 => Shape::Paper,
+            _ => Shape::Scissors,
         }
     }
 }
@@ -108,29 +96,25 @@ impl std::fmt::Display for Shape {
     }
 }
 
-pub fn build_strategy_map(s: &str) -> Vec<(Shape, Shape)> {
-    s.lines()
-        .map(|l| {
-            let mut it = l.split_whitespace().take(2);
-            let left = Shape::from_str(it.next().unwrap()).unwrap();
-            let right = Shape::from_str(it.next().unwrap()).unwrap();
-            (left, right)
-        })
-        .collect()
+pub fn build_strategy_map(s: &str) -> Result<Vec<(Shape, Shape)>, ParseError> {
+    build_rounds(s, Interpretation::Shape)
 }
 
-pub fn build_strategy_map_from_result(s: &str) -> Vec<(Shape, Shape)> {
-    s.lines()
-        .map(|l| {
-            let mut it = l.split_whitespace().take(2);
-            let left = Shape::from_str(it.next().unwrap()).unwrap();
-            // Get result of the round
-            let result = RoundResult::from_str(it.next().unwrap()).unwrap();
-            // Get Shape based on the round result
-            let right = result.get_shape_for_opponent(&left);
-            (left, right)
-        })
-        .collect()
+pub fn build_strategy_map_from_result(s: &str) -> Result<Vec<(Shape, Shape)>, ParseError> {
+    build_rounds(s, Interpretation::Outcome)
+}
+
+fn build_rounds(
+    s: &str,
+    interpretation: Interpretation,
+) -> Result<Vec<(Shape, Shape)>, ParseError> {
+    let game = Game::rock_paper_scissors();
+    let guide = Guide::parse(&game, interpretation, s)?;
+    let mapping = interpretation.default_mapping(&game);
+    Ok(guide
+        .rounds(&game, &mapping)
+        .map(|(op, you)| (Shape::from_index(op), Shape::from_index(you)))
+        .collect())
 }
 
 pub fn calculate_points(strategy: Vec<(Shape, Shape)>) -> u32 {
@@ -161,25 +145,8 @@ mod tests {
         Shape::from_str("D").unwrap();
     }
 
-    #[test]
-    fn test_simple_strategy_part1() {
-        let strategy = build_strategy_map(
-            "A Y
-        B X
-        C Z",
-        );
-        let points = calculate_points(strategy);
-        assert_eq!(points, 15);
-    }
-
-    #[test]
-    fn test_simple_strategy_part2() {
-        let strategy = build_strategy_map_from_result(
-            "A Y
-        B X
-        C Z",
-        );
-        let points = calculate_points(strategy);
-        assert_eq!(points, 12);
+    aoc_macros::examples! {
+        part1: |input| calculate_points(build_strategy_map(input).unwrap()),
+        part2: |input| calculate_points(build_strategy_map_from_result(input).unwrap()),
     }
 }
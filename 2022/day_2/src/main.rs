@@ -1,14 +1,28 @@
-use rock_paper_scissors::{build_strategy_map, build_strategy_map_from_result, calculate_points};
+use rock_paper_scissors::{
+    build_strategy_map, build_strategy_map_from_result, calculate_points,
+    game::{Game, Guide, Interpretation},
+};
 
 fn main() {
-    let content = include_str!("../input.txt");
+    let content = &aoc_common::puzzle_input!("../input.txt");
 
     // Part 1
-    let strategy = build_strategy_map(content);
+    let strategy = build_strategy_map(content).expect("valid strategy guide");
     let points = calculate_points(strategy);
     println!("Opponent shape/Your shape rounds result: {points}");
     // Part 2
-    let strategy = build_strategy_map_from_result(content);
+    let strategy = build_strategy_map_from_result(content).expect("valid strategy guide");
     let points = calculate_points(strategy);
     println!("Opponent shape/Round results result: {points}");
+
+    // Every other way of reading the second column
+    let game = Game::rock_paper_scissors();
+    for interpretation in [Interpretation::Shape, Interpretation::Outcome] {
+        let guide = Guide::parse(&game, interpretation, content).expect("valid strategy guide");
+        let report = guide.mapping_range(&game);
+        println!(
+            "{interpretation:?} mappings: best {} {:?}, worst {} {:?}",
+            report.best.score, report.best.mapping, report.worst.score, report.worst.mapping
+        );
+    }
 }
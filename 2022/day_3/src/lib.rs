@@ -1,97 +1,248 @@
-use std::collections::HashSet;
+use std::fmt::Debug;
 pub use std::str::FromStr;
 
-pub trait DuplicateItems {
-    fn duplicate_items(&self) -> HashSet<char>;
-    fn priority(&self) -> usize {
-        self.duplicate_items()
-            .iter()
-            .map(|c| Self::item_value(*c))
-            .sum()
+use aoc_common::prelude::*;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum RucksackError {
+    #[error("`{item}` at position {position} is not an item, expected a-z or A-Z")]
+    InvalidItem { item: char, position: usize },
+    #[error("{len} items can't be split into {compartments} equal compartments")]
+    UnevenCompartments { len: usize, compartments: usize },
+    #[error("rucksacks need at least one compartment")]
+    NoCompartment,
+    #[error("{count} rucksacks can't be split into groups of {size}")]
+    UnevenGroups { count: usize, size: usize },
+}
+
+/// Priority of an item: `a` to `z` are 1 to 26, `A` to `Z` are 27 to 52
+pub fn item_value(c: char) -> Option<usize> {
+    match c {
+        'a'..='z' => Some(c as usize - 'a' as usize + 1),
+        'A'..='Z' => Some(c as usize - 'A' as usize + 27),
+        _ => None,
+    }
+}
+
+fn item_from_value(value: usize) -> char {
+    match value {
+        1..=26 => (b'a' + (value - 1) as u8) as char,
+        _ => (b'A' + (value - 27) as u8) as char,
+    }
+}
+
+/// Set of item types, bit `n - 1` standing for the item of priority `n`
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemSet(u64);
+
+impl ItemSet {
+    /// Every item type, used as the starting point of intersections
+    pub const ALL: Self = Self((1 << 52) - 1);
+
+    pub fn from_items(items: &str) -> Result<Self, RucksackError> {
+        items
+            .chars()
+            .enumerate()
+            .try_fold(Self::default(), |set, (position, item)| {
+                let value =
+                    item_value(item).ok_or(RucksackError::InvalidItem { item, position })?;
+                Ok(Self(set.0 | 1 << (value - 1)))
+            })
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
     }
 
-    fn item_value(c: char) -> usize {
-        match c {
-            'a'..='z' => c as usize - 96, // 1..16
-            'A'..='Z' => c as usize - 38, // 27..52
-            _ => 0,
+    pub fn contains(&self, item: char) -> bool {
+        item_value(item).is_some_and(|value| self.0 & 1 << (value - 1) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Items of the set, by increasing priority
+    pub fn iter(&self) -> impl Iterator<Item = char> {
+        let bits = self.0;
+        (1..=52)
+            .filter(move |value| bits & 1 << (value - 1) != 0)
+            .map(item_from_value)
+    }
+
+    /// Sum of the priorities of the items in the set
+    pub fn priority(&self) -> usize {
+        let mut bits = self.0;
+        let mut sum = 0;
+        while bits != 0 {
+            sum += bits.trailing_zeros() as usize + 1;
+            bits &= bits - 1;
         }
+        sum
+    }
+}
+
+impl Debug for ItemSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
     }
 }
 
-#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub trait DuplicateItems {
+    fn duplicate_items(&self) -> ItemSet;
+    fn priority(&self) -> usize {
+        self.duplicate_items().priority()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Rucksack {
-    compartment: [Vec<char>; 2],
+    compartments: Vec<ItemSet>,
 }
 impl Rucksack {
-    pub fn new(first_comp: &str, second_comp: &str) -> Self {
-        let mut rucksack = Self {
-            compartment: [first_comp.chars().collect(), second_comp.chars().collect()],
-        };
-        rucksack.compartment[0].sort();
-        rucksack.compartment[1].sort();
-        rucksack
+    /// Splits the line in `compartments` parts of equal size
+    pub fn new(items: &str, compartments: usize) -> Result<Self, RucksackError> {
+        if compartments == 0 {
+            return Err(RucksackError::NoCompartment);
+        }
+        let chars = items.chars().collect::<Vec<_>>();
+        if chars.len() % compartments != 0 {
+            return Err(RucksackError::UnevenCompartments {
+                len: chars.len(),
+                compartments,
+            });
+        }
+
+        let size = (chars.len() / compartments).max(1);
+        let compartments = chars
+            .chunks(size)
+            .enumerate()
+            .map(|(idx, chunk)| {
+                ItemSet::from_items(&chunk.iter().collect::<String>()).map_err(|e| match e {
+                    RucksackError::InvalidItem { item, position } => RucksackError::InvalidItem {
+                        item,
+                        position: idx * size + position,
+                    },
+                    e => e,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { compartments })
+    }
+    pub fn compartments(&self) -> &[ItemSet] {
+        &self.compartments
     }
-    pub fn unique_items(&self) -> HashSet<char> {
-        let it = self.compartment.iter().flatten().cloned();
-        HashSet::from_iter(it)
+    pub fn unique_items(&self) -> ItemSet {
+        self.compartments
+            .iter()
+            .fold(ItemSet::default(), |acc, &set| acc.union(set))
     }
 }
 impl DuplicateItems for Rucksack {
-    fn duplicate_items(&self) -> HashSet<char> {
-        self.compartment[0]
-            .iter()
-            .filter(|c| self.compartment[1].binary_search(c).is_ok())
-            .cloned()
-            .collect()
+    /// Items found in every compartment
+    fn duplicate_items(&self) -> ItemSet {
+        match self.compartments.is_empty() {
+            true => ItemSet::default(),
+            false => self
+                .compartments
+                .iter()
+                .fold(ItemSet::ALL, |acc, &set| acc.intersection(set)),
+        }
     }
 }
 impl FromStr for Rucksack {
-    type Err = String;
+    type Err = RucksackError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() % 2 != 0 {
-            return Err("There must be equal amount of items in two compartments".to_string());
-        }
-        let (first_comp, second_comp) = s.split_at(s.len() / 2);
-        Ok(Rucksack::new(first_comp, second_comp))
+        Rucksack::new(s, 2)
     }
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
-pub struct RacksackGroup([Rucksack; 3]);
+/// Any number of rucksacks, their badge being the item they all carry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RacksackGroup(Vec<Rucksack>);
 
 impl RacksackGroup {
-    pub fn new(group: [Rucksack; 3]) -> Self {
+    pub fn new(group: Vec<Rucksack>) -> Self {
         Self(group)
     }
+
+    /// Cuts `rucksacks` into consecutive groups of `size`
+    pub fn split(rucksacks: &[Rucksack], size: usize) -> Result<Vec<Self>, RucksackError> {
+        if size == 0 || !rucksacks.len().is_multiple_of(size) {
+            return Err(RucksackError::UnevenGroups {
+                count: rucksacks.len(),
+                size,
+            });
+        }
+        Ok(rucksacks
+            .chunks_exact(size)
+            .map(|chunk| Self::new(chunk.to_vec()))
+            .collect())
+    }
 }
 
 impl DuplicateItems for RacksackGroup {
-    fn duplicate_items(&self) -> HashSet<char> {
-        let first = self.0[0].unique_items();
-        let second = self.0[1].unique_items();
-        let third = self.0[2].unique_items();
-
-        first
-            .into_iter()
-            .filter(|c| second.contains(c) && third.contains(c))
-            .collect()
+    fn duplicate_items(&self) -> ItemSet {
+        match self.0.is_empty() {
+            true => ItemSet::default(),
+            false => self.0.iter().fold(ItemSet::ALL, |acc, rucksack| {
+                acc.intersection(rucksack.unique_items())
+            }),
+        }
     }
 }
 
+/// One rucksack per line, with the given number of compartments
+pub fn parse_rucksacks(input: &str, compartments: usize) -> Result<Vec<Rucksack>, ParseError> {
+    lines_with(input, |line| {
+        Rucksack::new(line, compartments).map_err(|e| {
+            let column = match e {
+                RucksackError::InvalidItem { position, .. } => position + 1,
+                _ => 1,
+            };
+            ParseError::new(1, column, ParseErrorKind::Invalid(e.to_string()))
+        })
+    })
+}
+
+/// Sum of the priorities of the items found in both compartments
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+    Ok(parse_rucksacks(input, 2)?
+        .iter()
+        .map(|r| r.priority())
+        .sum())
+}
+
+/// Sum of the priorities of the badges of every group of three
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+    let rucksacks = parse_rucksacks(input, 2)?;
+    let groups = RacksackGroup::split(&rucksacks, 3).map_err(|e| {
+        ParseError::at_offset(input, input.len(), ParseErrorKind::Invalid(e.to_string()))
+    })?;
+    Ok(groups.iter().map(|g| g.priority()).sum())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    aoc_macros::examples! {
+        part1: |input| part1(input).unwrap(),
+        part2: |input| part2(input).unwrap(),
+    }
+
     #[test]
     fn test_task_example() {
-        let content = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
+        let content = include_str!("../examples/sample.txt");
 
         let racksacks: Vec<Rucksack> = content
             .lines()
@@ -102,16 +253,62 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
         let mut it = racksacks.iter();
         assert_eq!(
-            *it.next().unwrap().duplicate_items().iter().next().unwrap(),
+            it.next().unwrap().duplicate_items().iter().next().unwrap(),
             'p'
         );
         assert_eq!(
-            *it.next().unwrap().duplicate_items().iter().next().unwrap(),
+            it.next().unwrap().duplicate_items().iter().next().unwrap(),
             'L'
         );
         assert_eq!(
-            *it.next().unwrap().duplicate_items().iter().next().unwrap(),
+            it.next().unwrap().duplicate_items().iter().next().unwrap(),
             'P'
         );
     }
+
+    #[test]
+    fn item_sets() {
+        let set = ItemSet::from_items("aAzZa").unwrap();
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.priority(), 1 + 27 + 26 + 52);
+        assert_eq!(set.iter().collect::<String>(), "azAZ");
+        assert!(set.contains('Z') && !set.contains('b') && !set.contains('1'));
+        assert_eq!(ItemSet::ALL.len(), 52);
+    }
+
+    #[test]
+    fn any_group_size_and_compartments() {
+        let rucksacks = parse_rucksacks("abcXa\nXdefg\nhiXjk\nlmnoX", 1).unwrap();
+        let groups = RacksackGroup::split(&rucksacks, 4).unwrap();
+        assert_eq!(groups[0].duplicate_items().iter().collect::<String>(), "X");
+        assert_eq!(
+            RacksackGroup::split(&rucksacks, 3),
+            Err(RucksackError::UnevenGroups { count: 4, size: 3 })
+        );
+
+        let rucksack = Rucksack::new("abcbcd", 3).unwrap();
+        assert_eq!(rucksack.duplicate_items(), ItemSet::default());
+        let rucksack = Rucksack::new("abbcbd", 3).unwrap();
+        assert_eq!(rucksack.duplicate_items().iter().collect::<String>(), "b");
+    }
+
+    #[test]
+    fn invalid_rucksacks() {
+        assert_eq!(
+            Rucksack::from_str("abc"),
+            Err(RucksackError::UnevenCompartments {
+                len: 3,
+                compartments: 2
+            })
+        );
+        assert_eq!(
+            Rucksack::from_str("ab1d"),
+            Err(RucksackError::InvalidItem {
+                item: '1',
+                position: 2
+            })
+        );
+        let error = part1("vJrwpWtwJgWrhcsFMMfFFhFp\nab-d\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+    }
 }
@@ -1,42 +1,16 @@
-use rucksack_reorganization::*;
+use rucksack_reorganization::{part1, part2};
 
 fn main() {
-    let _content = "vJrwpWtwJgWrhcsFMMfFFhFp
-jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
-PmmdzqPrVvPwwTWBwg
-wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
-ttgJtRGJQctTZtZT
-CrZsJsPPZsGzwwsLwLmpwMDw";
-
-    let content = include_str!("../input.txt");
-
-    let racksacks: Vec<Rucksack> = content
-        .lines()
-        .map(|l| Rucksack::from_str(l).unwrap())
-        .collect();
-
-    let result: Vec<usize> = racksacks
-        .iter()
-        .map(|rack| {
-            // dbg!(&rack.unique_items());
-            rack.priority()
-        })
-        .collect::<Vec<_>>();
-    dbg!(result.iter().sum::<usize>());
-
-    if racksacks.len() % 3 != 0 {
-        panic!("number of racksacks could not be divided into groups of three");
+    let content = &aoc_common::puzzle_input!("../input.txt");
+
+    match (part1(content), part2(content)) {
+        (Ok(part1), Ok(part2)) => {
+            println!("Part 1 answer: {part1}");
+            println!("Part 2 answer: {part2}");
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
     }
-
-    let groups = racksacks
-        .chunks_exact(3)
-        .map(|chunk| RacksackGroup::new([chunk[0].clone(), chunk[1].clone(), chunk[2].clone()]));
-
-    // for gr in groups {
-    //     let items = gr.priority();
-    //     dbg!(items);
-    // }
-
-    let part_two_answer: usize = groups.map(|gr| gr.priority()).sum();
-    dbg!(part_two_answer);
 }
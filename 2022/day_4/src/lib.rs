@@ -0,0 +1,301 @@
+use std::{collections::BTreeSet, ops::RangeInclusive};
+
+use aoc_common::prelude::*;
+
+pub type Section = u32;
+pub type Sections = RangeInclusive<Section>;
+
+pub trait RangeInclusiveExt {
+    fn contains_range(&self, other: &Self) -> bool;
+    fn overlaps_range(&self, other: &Self) -> bool;
+    fn contains_or_is_contained(&self, other: &Self) -> bool {
+        self.contains_range(other) || other.contains_range(self)
+    }
+}
+
+impl<T> RangeInclusiveExt for RangeInclusive<T>
+where
+    T: PartialOrd,
+{
+    fn contains_range(&self, other: &Self) -> bool {
+        self.contains(other.start()) && self.contains(other.end())
+    }
+    /// Symmetric, and ranges sharing only one end overlap
+    fn overlaps_range(&self, other: &Self) -> bool {
+        self.start() <= other.end() && other.start() <= self.end()
+    }
+}
+
+/// The two assignments of a line
+pub type Pair = (Sections, Sections);
+
+pub fn parse_pairs(input: &str) -> Result<Vec<Pair>, ParseError> {
+    lines_with(input, |line| {
+        let (first, second) = split_pair(line, ',')?;
+        let first = parse_sections(first).map_err(|e| e.within(line, first))?;
+        let second = parse_sections(second).map_err(|e| e.within(line, second))?;
+        Ok((first, second))
+    })
+}
+
+fn parse_sections(text: &str) -> Result<Sections, ParseError> {
+    let (start, end) = split_pair(text, '-')?;
+    let number = |part: &str| {
+        part.parse::<Section>()
+            .map_err(|_| ParseError::at(text, part, ParseErrorKind::InvalidNumber(part.to_owned())))
+    };
+    let (start, end) = (number(start)?, number(end)?);
+    if start > end {
+        return Err(ParseError::new(
+            1,
+            1,
+            ParseErrorKind::Invalid(format!("range {start}-{end} ends before it starts")),
+        ));
+    }
+    Ok(start..=end)
+}
+
+/// Assignments of every elf, the pair of line `n` being elves `2n - 2` and
+/// `2n - 1`.
+///
+/// Elves are 0-based indexes into the returned list here and in the functions
+/// taking it, while the puzzle numbers them from 1.
+pub fn elves(pairs: &[Pair]) -> Vec<Sections> {
+    pairs
+        .iter()
+        .flat_map(|(first, second)| [first.clone(), second.clone()])
+        .collect()
+}
+
+/// Number of pairs where one assignment fully contains the other
+pub fn fully_contained_count(pairs: &[Pair]) -> usize {
+    pairs
+        .iter()
+        .filter(|(first, second)| first.contains_or_is_contained(second))
+        .count()
+}
+
+/// Number of pairs sharing at least one section
+pub fn overlapping_count(pairs: &[Pair]) -> usize {
+    pairs
+        .iter()
+        .filter(|(first, second)| first.overlaps_range(second))
+        .count()
+}
+
+/// For every elf, the sorted list of the other elves it shares a section with,
+/// all of them as 0-based indexes into `elves`.
+///
+/// Sweeps the assignments by start section, keeping the ones still open
+/// ordered by their end, so the cost is `O(n log n)` plus the size of the
+/// answer.
+pub fn overlap_graph(elves: &[Sections]) -> Vec<Vec<usize>> {
+    let mut order = (0..elves.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&elf| (*elves[elf].start(), *elves[elf].end()));
+
+    let mut graph = vec![Vec::new(); elves.len()];
+    let mut open: BTreeSet<(Section, usize)> = BTreeSet::new();
+    for elf in order {
+        let start = *elves[elf].start();
+        while let Some(&(end, closed)) = open.first() {
+            if end >= start {
+                break;
+            }
+            open.remove(&(end, closed));
+        }
+        for &(_, other) in &open {
+            graph[elf].push(other);
+            graph[other].push(elf);
+        }
+        open.insert((*elves[elf].end(), elf));
+    }
+
+    graph.iter_mut().for_each(|others| others.sort_unstable());
+    graph
+}
+
+/// Consecutive sections covered by the same number of elves
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub sections: Sections,
+    pub elves: usize,
+}
+
+impl Segment {
+    /// Number of sections, at least one
+    pub fn size(&self) -> usize {
+        (self.sections.end() - self.sections.start()) as usize + 1
+    }
+}
+
+/// How many elves cover each section of an area
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    pub segments: Vec<Segment>,
+}
+
+impl Coverage {
+    /// Coverage of `area` by the elves' assignments, built from the start and
+    /// end events of every assignment
+    pub fn new(elves: &[Sections], area: Sections) -> Self {
+        // +1 when an assignment starts, -1 on the section after its end
+        let mut events = elves
+            .iter()
+            .filter(|sections| sections.overlaps_range(&area))
+            .flat_map(|sections| {
+                let start = *sections.start().max(area.start());
+                let end = *sections.end().min(area.end());
+                [(start as u64, 1i64), (end as u64 + 1, -1)]
+            })
+            .collect::<Vec<_>>();
+        events.sort_unstable();
+
+        let mut segments = Vec::new();
+        let mut position = *area.start() as u64;
+        let mut count = 0i64;
+        let area_end = *area.end() as u64;
+        for (at, delta) in events {
+            if at > position {
+                segments.push(Segment {
+                    sections: position as Section..=(at - 1) as Section,
+                    elves: count as usize,
+                });
+                position = at;
+            }
+            count += delta;
+        }
+        if position <= area_end {
+            segments.push(Segment {
+                sections: position as Section..=area_end as Section,
+                elves: count as usize,
+            });
+        }
+
+        Self { segments }
+    }
+
+    /// Sections covered by no elf
+    pub fn uncovered(&self) -> impl Iterator<Item = &Segment> {
+        self.segments.iter().filter(|s| s.elves == 0)
+    }
+
+    /// Sections covered by exactly one elf
+    pub fn single(&self) -> impl Iterator<Item = &Segment> {
+        self.segments.iter().filter(|s| s.elves == 1)
+    }
+
+    /// Sections covered by two elves or more
+    pub fn shared(&self) -> impl Iterator<Item = &Segment> {
+        self.segments.iter().filter(|s| s.elves > 1)
+    }
+
+    /// First run of sections covered by the most elves
+    pub fn most_crowded(&self) -> Option<&Segment> {
+        self.segments
+            .iter()
+            .rev()
+            .max_by_key(|s| s.elves)
+            .filter(|s| s.elves > 0)
+    }
+}
+
+/// Number of sections in the segments
+pub fn section_count<'a>(segments: impl Iterator<Item = &'a Segment>) -> usize {
+    segments.map(Segment::size).sum()
+}
+
+pub fn part1(input: &str) -> Result<usize, ParseError> {
+    Ok(fully_contained_count(&parse_pairs(input)?))
+}
+
+pub fn part2(input: &str) -> Result<usize, ParseError> {
+    Ok(overlapping_count(&parse_pairs(input)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_macros::examples! {
+        part1: |input| part1(input).unwrap(),
+        part2: |input| part2(input).unwrap(),
+    }
+
+    #[test]
+    fn overlap_rules() {
+        assert!((5..=7).overlaps_range(&(7..=9)));
+        assert!((7..=9).overlaps_range(&(5..=7)));
+        assert!((2..=8).overlaps_range(&(3..=7)));
+        assert!((3..=7).overlaps_range(&(2..=8)));
+        assert!(!(2..=4).overlaps_range(&(6..=8)));
+        assert!((6..=6).contains_or_is_contained(&(4..=6)));
+    }
+
+    #[test]
+    fn graph_matches_pairwise_check() {
+        let pairs = parse_pairs(include_str!("../examples/sample.txt")).unwrap();
+        let elves = elves(&pairs);
+        let graph = overlap_graph(&elves);
+        for (elf, others) in graph.iter().enumerate() {
+            let expected = (0..elves.len())
+                .filter(|&other| other != elf && elves[elf].overlaps_range(&elves[other]))
+                .collect::<Vec<_>>();
+            assert_eq!(*others, expected, "elf {elf}");
+        }
+        assert_eq!(graph[0], [2, 3, 6, 7, 9, 10, 11]);
+    }
+
+    #[test]
+    fn coverage() {
+        let elves = [2..=4, 3..=5, 8..=8];
+        let coverage = Coverage::new(&elves, 1..=10);
+        assert_eq!(
+            coverage.segments,
+            [
+                Segment {
+                    sections: 1..=1,
+                    elves: 0
+                },
+                Segment {
+                    sections: 2..=2,
+                    elves: 1
+                },
+                Segment {
+                    sections: 3..=4,
+                    elves: 2
+                },
+                Segment {
+                    sections: 5..=5,
+                    elves: 1
+                },
+                Segment {
+                    sections: 6..=7,
+                    elves: 0
+                },
+                Segment {
+                    sections: 8..=8,
+                    elves: 1
+                },
+                Segment {
+                    sections: 9..=10,
+                    elves: 0
+                },
+            ]
+        );
+        assert_eq!(section_count(coverage.uncovered()), 5);
+        assert_eq!(section_count(coverage.single()), 3);
+        assert_eq!(section_count(coverage.shared()), 2);
+        assert_eq!(coverage.most_crowded().unwrap().sections, 3..=4);
+        assert_eq!(Coverage::new(&[], 1..=3).most_crowded(), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = parse_pairs("2-4,6-8\n2-3;4-5\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 8));
+        let error = parse_pairs("2-4,6-x\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 7));
+        let error = parse_pairs("2-4,8-6\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+    }
+}
@@ -1,76 +1,45 @@
-use std::ops::RangeInclusive;
-
-use color_eyre::{eyre, Report, Result};
-
-trait RangeInclusiveExt {
-    fn contains_range(&self, other: &Self) -> bool;
-    fn overlaps_range(&self, other: &Self) -> bool;
-    fn contains_or_is_contained(&self, other: &Self) -> bool {
-        self.contains_range(other) || other.contains_range(self)
-    }
-    fn overlaps_or_is_overlaped(&self, other: &Self) -> bool {
-        self.overlaps_range(other) || other.overlaps_range(self)
-    }
-}
-
-impl<T> RangeInclusiveExt for RangeInclusive<T>
-where
-    T: PartialOrd,
-{
-    fn contains_range(&self, other: &Self) -> bool {
-        self.contains(other.start()) && self.contains(other.end())
-    }
-    fn overlaps_range(&self, other: &Self) -> bool {
-        self.contains(other.start()) || self.contains(other.end())
-    }
-}
-
-fn main() -> Result<(), Report> {
-    let mut contains: u32 = 0;
-    let mut overlaps: u32 = 0;
-
-    for line in include_str!("../input.txt").lines() {
-        let ranges = line
-            .split(',')
-            .map(|range| {
-                let mut range_it = range.split('-');
-                let start = range_it
-                    .next()
-                    .ok_or_else(|| eyre::eyre!("Expected start of range"))?
-                    .parse::<u32>()?;
-                let end = range_it
-                    .next()
-                    .ok_or_else(|| eyre::eyre!("Expected end of range"))?
-                    .parse::<u32>()?;
-                Ok(start..=end)
-            })
-            .collect::<Result<Vec<_>>>()?;
-        let (first, second) = collect_to_tuple(ranges)?;
-
-        if first.contains_or_is_contained(&second) {
-            contains += 1;
-        }
-        if first.overlaps_or_is_overlaped(&second) {
-            // println!("overlaping pair: {first:?} - {second:?}");
-            overlaps += 1;
+use camp_cleanup::{
+    elves, fully_contained_count, overlap_graph, overlapping_count, parse_pairs, section_count,
+    Coverage,
+};
+
+fn main() {
+    let content = &aoc_common::puzzle_input!("../input.txt");
+    let pairs = match parse_pairs(content) {
+        Ok(pairs) => pairs,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
         }
+    };
+
+    println!(
+        "Pairs fully containing the other: {}",
+        fully_contained_count(&pairs)
+    );
+    println!("Overlapping pairs: {}", overlapping_count(&pairs));
+
+    let elves = elves(&pairs);
+    let graph = overlap_graph(&elves);
+    if let Some((elf, others)) = graph.iter().enumerate().max_by_key(|(_, o)| o.len()) {
+        println!("Elf {} overlaps the most elves: {}", elf + 1, others.len());
     }
 
-    dbg!(contains);
-    dbg!(overlaps);
-    Ok(())
-}
-
-fn collect_to_tuple<T>(ranges_vec: Vec<T>) -> Result<(T, T)> {
-    if ranges_vec.len() != 2 {
-        Err(eyre::eyre!("Expected exactly two ranges"))?;
+    let last = elves.iter().map(|s| *s.end()).max().unwrap_or(1);
+    let coverage = Coverage::new(&elves, 1..=last);
+    println!(
+        "Uncovered sections: {}",
+        section_count(coverage.uncovered())
+    );
+    println!(
+        "Sections covered once: {}",
+        section_count(coverage.single())
+    );
+    println!("Shared sections: {}", section_count(coverage.shared()));
+    if let Some(crowded) = coverage.most_crowded() {
+        println!(
+            "Most crowded sections: {:?} with {} elves",
+            crowded.sections, crowded.elves
+        );
     }
-    let mut ranges_it = ranges_vec.into_iter();
-    let first = ranges_it
-        .next()
-        .ok_or_else(|| eyre::eyre!("Start range not found"))?;
-    let second = ranges_it
-        .next()
-        .ok_or_else(|| eyre::eyre!("End range not found"))?;
-    Ok((first, second))
 }
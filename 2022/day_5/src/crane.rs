@@ -0,0 +1,231 @@
+use std::num::NonZeroUsize;
+
+use crate::{MoveCommand, Stacks};
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum CraneError {
+    #[error("there is no stack {stack}, stacks go from 1 to {count}")]
+    NoSuchStack { stack: usize, count: usize },
+    #[error("can't move {requested} crates from stack {stack}, it only has {available}")]
+    NotEnoughCrates {
+        stack: usize,
+        requested: usize,
+        available: usize,
+    },
+    #[error("a crane must lift at least one crate at a time")]
+    ZeroCapacity,
+}
+
+/// How a crane splits a move into lifts.
+///
+/// Every lift takes crates from the top of a stack and puts them down in the
+/// same order, so a crane lifting one crate at a time reverses them.
+pub trait Crane {
+    /// Most crates taken in a single lift
+    fn capacity(&self) -> NonZeroUsize;
+
+    /// Sizes of the lifts needed to move `count` crates, in order
+    fn lifts(&self, count: usize) -> Vec<usize> {
+        let capacity = self.capacity().get();
+        let mut lifts = vec![capacity; count / capacity];
+        if !count.is_multiple_of(capacity) {
+            lifts.push(count % capacity);
+        }
+        lifts
+    }
+}
+
+/// Moves crates one at a time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn capacity(&self) -> NonZeroUsize {
+        NonZeroUsize::MIN
+    }
+}
+
+/// Moves any number of crates at once
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn capacity(&self) -> NonZeroUsize {
+        NonZeroUsize::MAX
+    }
+}
+
+/// Moves at most the given number of crates at once
+#[derive(Debug, Clone, Copy)]
+pub struct CapacityCrane(NonZeroUsize);
+
+impl CapacityCrane {
+    pub fn new(capacity: usize) -> Result<Self, CraneError> {
+        NonZeroUsize::new(capacity)
+            .map(Self)
+            .ok_or(CraneError::ZeroCapacity)
+    }
+}
+
+impl Crane for CapacityCrane {
+    fn capacity(&self) -> NonZeroUsize {
+        self.0
+    }
+}
+
+/// Runs move commands on the stacks, validating every command before touching
+/// them and logging it so that it can be undone
+#[derive(Debug, Clone)]
+pub struct Simulator<C> {
+    stacks: Stacks,
+    crane: C,
+    log: Vec<MoveCommand>,
+}
+
+impl<C: Crane> Simulator<C> {
+    pub fn new(stacks: Stacks, crane: C) -> Self {
+        Self {
+            stacks,
+            crane,
+            log: Vec::new(),
+        }
+    }
+
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    /// Commands applied so far, oldest first
+    pub fn log(&self) -> &[MoveCommand] {
+        &self.log
+    }
+
+    fn validate(&self, command: MoveCommand) -> Result<(), CraneError> {
+        let count = self.stacks.stacks().len();
+        for stack in [command.from(), command.to()] {
+            if self.stacks.stack(stack).is_none() {
+                return Err(CraneError::NoSuchStack { stack, count });
+            }
+        }
+        let available = self.stacks.stack(command.from()).map_or(0, |s| s.len());
+        if command.count() > available {
+            return Err(CraneError::NotEnoughCrates {
+                stack: command.from(),
+                requested: command.count(),
+                available,
+            });
+        }
+        Ok(())
+    }
+
+    fn lift(&mut self, from: usize, to: usize, size: usize) {
+        let crates = self
+            .stacks
+            .stack_mut(from)
+            .expect("validated stack")
+            .take(size);
+        self.stacks
+            .stack_mut(to)
+            .expect("validated stack")
+            .put(crates);
+    }
+
+    /// Applies `command`, leaving the stacks untouched when it is invalid
+    pub fn step(&mut self, command: MoveCommand) -> Result<(), CraneError> {
+        self.validate(command)?;
+        for size in self.crane.lifts(command.count()) {
+            self.lift(command.from(), command.to(), size);
+        }
+        self.log.push(command);
+        Ok(())
+    }
+
+    /// Applies every command, stopping at the first invalid one
+    pub fn run<I>(&mut self, commands: I) -> Result<(), CraneError>
+    where
+        I: IntoIterator<Item = MoveCommand>,
+    {
+        commands
+            .into_iter()
+            .try_for_each(|command| self.step(command))
+    }
+
+    /// Reverts the last applied command and returns it.
+    ///
+    /// Lifts keep the order of the crates, so putting back every lift in
+    /// reverse order restores the stacks exactly.
+    pub fn undo(&mut self) -> Option<MoveCommand> {
+        let command = self.log.pop()?;
+        for size in self.crane.lifts(command.count()).into_iter().rev() {
+            self.lift(command.to(), command.from(), size);
+        }
+        Some(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Stacks {
+        Stacks::parse("    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ").unwrap()
+    }
+
+    #[test]
+    fn capacity_splits_lifts() {
+        assert_eq!(CrateMover9000.lifts(3), [1, 1, 1]);
+        assert_eq!(CrateMover9001.lifts(3), [3]);
+        let crane = CapacityCrane::new(2).unwrap();
+        assert_eq!(crane.lifts(5), [2, 2, 1]);
+        assert!(crane.lifts(0).is_empty());
+        assert_eq!(CapacityCrane::new(0).unwrap_err(), CraneError::ZeroCapacity);
+
+        let mut simulator = Simulator::new(sample(), crane);
+        simulator.step(MoveCommand::new(3, 2, 3)).unwrap();
+        assert_eq!(
+            simulator.stacks().to_string().lines().next(),
+            Some("        [M]")
+        );
+        assert_eq!(simulator.stacks().tops(), "NM");
+    }
+
+    #[test]
+    fn invalid_moves_leave_stacks_alone() {
+        let mut simulator = Simulator::new(sample(), CrateMover9000);
+        assert_eq!(
+            simulator.step(MoveCommand::new(1, 4, 1)),
+            Err(CraneError::NoSuchStack { stack: 4, count: 3 })
+        );
+        assert_eq!(
+            simulator.step(MoveCommand::new(1, 0, 1)),
+            Err(CraneError::NoSuchStack { stack: 0, count: 3 })
+        );
+        assert_eq!(
+            simulator.step(MoveCommand::new(4, 2, 1)),
+            Err(CraneError::NotEnoughCrates {
+                stack: 2,
+                requested: 4,
+                available: 3
+            })
+        );
+        assert_eq!(*simulator.stacks(), sample());
+        assert!(simulator.log().is_empty());
+    }
+
+    #[test]
+    fn undo_restores_stacks() {
+        let commands = [
+            MoveCommand::new(1, 2, 1),
+            MoveCommand::new(3, 1, 3),
+            MoveCommand::new(2, 2, 1),
+            MoveCommand::new(1, 1, 2),
+        ];
+        for capacity in 1..=4 {
+            let mut simulator = Simulator::new(sample(), CapacityCrane::new(capacity).unwrap());
+            simulator.run(commands).unwrap();
+            assert_eq!(simulator.log(), commands);
+            while simulator.undo().is_some() {}
+            assert_eq!(*simulator.stacks(), sample(), "capacity {capacity}");
+        }
+    }
+}
@@ -1,8 +1,20 @@
 use std::{fmt::Display, num::ParseIntError, ops::Deref, str::FromStr};
 
-#[derive(Clone, Copy)]
+use aoc_common::prelude::*;
+
+pub mod crane;
+
+pub use crane::{CapacityCrane, Crane, CraneError, CrateMover9000, CrateMover9001, Simulator};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Crate(char);
 
+impl Crate {
+    pub fn new(label: char) -> Self {
+        Self(label)
+    }
+}
+
 impl Deref for Crate {
     type Target = char;
 
@@ -23,7 +35,7 @@ impl std::fmt::Debug for Crate {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Stack(Vec<Crate>);
 
 impl Stack {
@@ -39,6 +51,130 @@ impl Stack {
     pub fn top(&self) -> Option<Crate> {
         self.0.last().copied()
     }
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+    /// Crate at `height`, the bottom one being at height 0
+    pub fn get(&self, height: usize) -> Option<Crate> {
+        self.0.get(height).copied()
+    }
+    /// Removes the `count` top crates, keeping their order
+    pub fn take(&mut self, count: usize) -> Vec<Crate> {
+        let at = self.0.len().saturating_sub(count);
+        self.0.split_off(at)
+    }
+    /// Puts `crates` on top, the first one lowest
+    pub fn put(&mut self, crates: Vec<Crate>) {
+        self.0.extend(crates)
+    }
+}
+
+/// The stacks of the drawing, stack 1 first
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Stacks(Vec<Stack>);
+
+impl Stacks {
+    pub fn new(stacks: Vec<Stack>) -> Self {
+        Self(stacks)
+    }
+
+    /// Reads a drawing: rows of `[X]` crates, topmost first, over a line of
+    /// stack numbers.
+    ///
+    /// Lines don't need to be padded to the width of the drawing.
+    pub fn parse(drawing: &str) -> Result<Self, ParseError> {
+        let mut lines = drawing.lines().collect::<Vec<_>>();
+        let labels = lines.pop().ok_or_else(|| {
+            ParseError::new(1, 1, ParseErrorKind::Expected("stack numbers".to_owned()))
+        })?;
+        let count = labels.split_whitespace().count();
+        if count == 0 {
+            return Err(ParseError::at(
+                drawing,
+                labels,
+                ParseErrorKind::Expected("stack numbers".to_owned()),
+            ));
+        }
+
+        let mut stacks = vec![Stack::new(); count];
+        for line in lines.into_iter().rev() {
+            for (idx, chunk) in line.as_bytes().chunks(4).enumerate() {
+                let error = |column: usize, kind| {
+                    ParseError::at_offset(line, idx * 4 + column, kind).within(drawing, line)
+                };
+                match chunk {
+                    [b' ', b' ', b' ', ..] | [b' '] | [b' ', b' '] => continue,
+                    [b'[', label, b']', ..] if label.is_ascii_graphic() => {
+                        if idx >= count {
+                            let kind = ParseErrorKind::Invalid(format!(
+                                "crate outside of the {count} stacks"
+                            ));
+                            return Err(error(0, kind));
+                        }
+                        stacks[idx].push(Crate(*label as char));
+                    }
+                    _ => {
+                        return Err(error(
+                            0,
+                            ParseErrorKind::Expected("`[X]` or blank".to_owned()),
+                        ))
+                    }
+                }
+                if chunk.get(3).is_some_and(|&sep| sep != b' ') {
+                    return Err(error(3, ParseErrorKind::Expected("` `".to_owned())));
+                }
+            }
+        }
+        Ok(Self(stacks))
+    }
+
+    pub fn stacks(&self) -> &[Stack] {
+        &self.0
+    }
+
+    /// Stack `number`, the first one being stack 1
+    pub fn stack(&self, number: usize) -> Option<&Stack> {
+        number.checked_sub(1).and_then(|idx| self.0.get(idx))
+    }
+
+    pub(crate) fn stack_mut(&mut self, number: usize) -> Option<&mut Stack> {
+        number.checked_sub(1).and_then(|idx| self.0.get_mut(idx))
+    }
+
+    /// Crates on top of every stack, empty stacks being skipped
+    pub fn tops(&self) -> String {
+        self.0
+            .iter()
+            .filter_map(|stack| stack.top())
+            .map(|crt| *crt)
+            .collect()
+    }
+}
+
+/// Draws the stacks back the way the puzzle does, every line padded to the
+/// width of the drawing
+impl Display for Stacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let height = self.0.iter().map(Stack::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let row = self
+                .0
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(crt) => format!("[{crt}]"),
+                    None => "   ".to_owned(),
+                })
+                .collect::<Vec<_>>();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        let labels = (1..=self.0.len())
+            .map(|number| format!(" {number} "))
+            .collect::<Vec<_>>();
+        write!(f, "{}", labels.join(" "))
+    }
 }
 
 pub fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
@@ -55,22 +191,23 @@ pub fn transpose<T>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
         .collect()
 }
 
+/// Crates of one line of the drawing, `None` where a stack has no crate at
+/// that height.
+///
+/// Lines may stop right after their last crate, missing stacks on the right
+/// are then left out.
 pub fn parse_into_crates(s: &str) -> Vec<Option<Crate>> {
     s.chars()
         .collect::<Vec<_>>()
         .chunks(4)
-        .map(|chunk_crate| {
-            // println!("chunk_crate: {chunk_crate:?}");
-            match chunk_crate.get(1) {
-                Some(' ') => None,
-                Some(c) => Some(Crate(*c)),
-                None => panic!("Don't know how this could have happen"),
-            }
+        .map(|chunk_crate| match chunk_crate.get(1) {
+            Some(' ') | None => None,
+            Some(c) => Some(Crate(*c)),
         })
         .collect()
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct MoveCommand {
     count: usize,
     from: usize,
@@ -78,6 +215,9 @@ pub struct MoveCommand {
 }
 
 impl MoveCommand {
+    pub fn new(count: usize, from: usize, to: usize) -> Self {
+        Self { count, from, to }
+    }
     pub fn count(&self) -> usize {
         self.count
     }
@@ -98,7 +238,7 @@ impl FromStr for MoveCommand {
         if tokens.len() != 6 {
             return Err(CommandError::WrongLength);
         }
-        if !tokens.first().is_some_and(|t| *t == "move") {
+        if tokens.first().is_none_or(|t| *t != "move") {
             return Err(CommandError::WrongCommand);
         }
 
@@ -110,27 +250,6 @@ impl FromStr for MoveCommand {
     }
 }
 
-pub fn move_crate(stacks: &mut [Stack], command: MoveCommand) {
-    for _ in 1..=command.count() {
-        let poped_from = stacks.get_mut(command.from() - 1).unwrap().pop().unwrap();
-        let to = stacks.get_mut(command.to() - 1).unwrap();
-        to.push(poped_from);
-    }
-}
-
-pub fn move_crate_and_retain_order(stacks: &mut [Stack], command: MoveCommand) {
-    let poped_from = (1..=command.count())
-        .map(|_| stacks.get_mut(command.from() - 1).unwrap().pop())
-        .collect::<Option<Vec<_>>>()
-        .expect("expected to pop all elements from stack");
-
-    let to = stacks.get_mut(command.to() - 1).unwrap();
-
-    for next_to_push in poped_from.into_iter().rev() {
-        to.push(next_to_push);
-    }
-}
-
 #[derive(Debug)]
 pub enum CommandError {
     ParseInt(ParseIntError),
@@ -159,3 +278,85 @@ impl From<ParseIntError> for CommandError {
 }
 
 impl std::error::Error for CommandError {}
+
+/// Starting stacks and the rearrangement procedure of the puzzle input
+#[derive(Debug, Clone)]
+pub struct Procedure<'a> {
+    pub stacks: Stacks,
+    /// Commands along with the input line they come from
+    pub commands: Vec<(&'a str, MoveCommand)>,
+}
+
+pub fn parse_procedure(input: &str) -> Result<Procedure<'_>, ParseError> {
+    let mut blocks = blocks(input);
+    let drawing = blocks
+        .next()
+        .ok_or_else(|| ParseError::at_offset(input, input.len(), ParseErrorKind::UnexpectedEnd))?;
+    let stacks = Stacks::parse(drawing).map_err(|e| e.within(input, drawing))?;
+    let commands = blocks
+        .flat_map(str::lines)
+        .map(|line| {
+            line.parse::<MoveCommand>()
+                .map(|command| (line, command))
+                .map_err(|e| ParseError::at(input, line, ParseErrorKind::Invalid(e.to_string())))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Procedure { stacks, commands })
+}
+
+/// Runs the whole procedure with `crane` and reads the top crates
+pub fn rearrange<C: Crane>(input: &str, crane: C) -> Result<String, ParseError> {
+    let Procedure { stacks, commands } = parse_procedure(input)?;
+    let mut simulator = Simulator::new(stacks, crane);
+    for (line, command) in commands {
+        simulator
+            .step(command)
+            .map_err(|e| ParseError::at(input, line, ParseErrorKind::Invalid(e.to_string())))?;
+    }
+    Ok(simulator.stacks().tops())
+}
+
+pub fn part1(input: &str) -> Result<String, ParseError> {
+    rearrange(input, CrateMover9000)
+}
+
+pub fn part2(input: &str) -> Result<String, ParseError> {
+    rearrange(input, CrateMover9001)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_macros::examples! {
+        part1: |input| part1(input).unwrap(),
+        part2: |input| part2(input).unwrap(),
+    }
+
+    #[test]
+    fn drawing_round_trip() {
+        let input = include_str!("../examples/sample.txt");
+        let drawing = blocks(input).next().unwrap();
+        let stacks = Stacks::parse(drawing).unwrap();
+        assert_eq!(stacks.tops(), "NDP");
+        assert_eq!(stacks.to_string(), drawing.trim_end_matches('\n'));
+    }
+
+    #[test]
+    fn ragged_drawing() {
+        let stacks = Stacks::parse("    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3").unwrap();
+        assert_eq!(stacks.stack(2).unwrap().len(), 3);
+        assert_eq!(stacks.tops(), "NDP");
+        assert_eq!(parse_into_crates("    [D]").len(), 2);
+    }
+
+    #[test]
+    fn drawing_errors() {
+        let error = Stacks::parse("[A] [B] [C]\n 1   2").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 9));
+        let error = Stacks::parse("[A] (B)\n 1   2").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 5));
+        let error = parse_procedure("[A]\n 1 \n\nmove 1 from 1 to\n").unwrap_err();
+        assert_eq!((error.line, error.column), (4, 1));
+    }
+}
@@ -1,141 +1,349 @@
-use std::collections::HashSet;
-
-/// # My Solution
-/// Subroutine that detects a start-of-packet marker in the datastream.
-/// In the protocol being used by the Elves,
-/// the start of a packet is indicated
-/// by a sequence of four characters that are all different.
-pub fn get_marker_index(s: &str, seq_size: usize) -> Option<usize> {
-    let mut idx: usize = 0;
-    while let Some(s) = s.get(idx..idx + seq_size) {
-        // dbg!(s);
-        if let Some(advance) = find_duplicate_index(s) {
-            idx += advance + 1;
-            continue;
-        }
-        return Some(idx + seq_size);
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    io::{self, BufRead},
+};
+
+/// Where a marker ends in the datastream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Marker {
+    /// Characters read up to the end of the marker, the puzzle answer
+    pub position: usize,
+    /// Bytes read up to the end of the marker
+    pub offset: usize,
+}
+
+/// Rolling check that the last `WINDOW` characters are all different.
+///
+/// Counts of the characters in the window are kept up to date as characters
+/// come in and go out. ASCII characters are tracked in bitmasks of the ones
+/// present and the ones seen more than once, the rest in a map.
+#[derive(Debug, Clone)]
+pub struct Detector<const WINDOW: usize> {
+    ring: [char; WINDOW],
+    position: usize,
+    offset: usize,
+    ascii: [u32; 128],
+    present: u128,
+    repeated: u128,
+    others: HashMap<char, u32>,
+    repeated_others: usize,
+}
+
+impl<const WINDOW: usize> Default for Detector<WINDOW> {
+    fn default() -> Self {
+        Self::new()
     }
-    None
 }
 
-fn find_duplicate_index(s: &str) -> Option<usize> {
-    let mut unique_chars = HashSet::new();
-    s.char_indices()
-        .find_map(|(i, c)| match unique_chars.insert(c).then_some(i) {
-            Some(_) => None,
-            None => s.chars().position(|i| i == c),
+impl<const WINDOW: usize> Detector<WINDOW> {
+    pub fn new() -> Self {
+        Self {
+            ring: ['\0'; WINDOW],
+            position: 0,
+            offset: 0,
+            ascii: [0; 128],
+            present: 0,
+            repeated: 0,
+            others: HashMap::new(),
+            repeated_others: 0,
+        }
+    }
+
+    /// Characters pushed so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Number of different characters in the window
+    pub fn distinct(&self) -> usize {
+        self.present.count_ones() as usize + self.others.len()
+    }
+
+    fn add(&mut self, c: char) {
+        match c.is_ascii() {
+            true => {
+                let idx = c as usize;
+                self.ascii[idx] += 1;
+                self.present |= 1 << idx;
+                if self.ascii[idx] == 2 {
+                    self.repeated |= 1 << idx;
+                }
+            }
+            false => {
+                let count = self.others.entry(c).or_default();
+                *count += 1;
+                if *count == 2 {
+                    self.repeated_others += 1;
+                }
+            }
+        }
+    }
+
+    fn remove(&mut self, c: char) {
+        match c.is_ascii() {
+            true => {
+                let idx = c as usize;
+                self.ascii[idx] -= 1;
+                match self.ascii[idx] {
+                    0 => self.present &= !(1 << idx),
+                    1 => self.repeated &= !(1 << idx),
+                    _ => {}
+                }
+            }
+            false => {
+                let count = self.others.get_mut(&c).expect("character in the window");
+                *count -= 1;
+                match *count {
+                    0 => {
+                        self.others.remove(&c);
+                    }
+                    1 => self.repeated_others -= 1,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Adds the next character, returning the marker ending with it if the
+    /// last `WINDOW` characters are all different
+    pub fn push(&mut self, c: char) -> Option<Marker> {
+        if WINDOW == 0 {
+            return None;
+        }
+        let slot = self.position % WINDOW;
+        if self.position >= WINDOW {
+            self.remove(self.ring[slot]);
+        }
+        self.ring[slot] = c;
+        self.add(c);
+        self.position += 1;
+        self.offset += c.len_utf8();
+
+        let unique = self.position >= WINDOW && self.repeated == 0 && self.repeated_others == 0;
+        unique.then_some(Marker {
+            position: self.position,
+            offset: self.offset,
         })
+    }
 }
 
-/// Ring buffer based solution
-pub fn marker_start(input: &str) -> usize {
-    const PREV_SIZE: usize = 3;
-    let mut prev = [' '; PREV_SIZE];
-    prev.copy_from_slice(&input.chars().collect::<Vec<_>>()[..PREV_SIZE]);
-    for (ix, c) in input.chars().skip(PREV_SIZE).enumerate() {
-        if !prev.contains(&c) && is_uniq(&prev) {
-            return ix + PREV_SIZE + 1;
-        } else {
-            prev[ix % PREV_SIZE] = c;
+/// Iterator over every marker of a stream of characters, overlapping ones
+/// included
+pub struct Markers<const WINDOW: usize, I> {
+    chars: I,
+    detector: Detector<WINDOW>,
+}
+
+impl<const WINDOW: usize, I, E> Markers<WINDOW, I>
+where
+    I: Iterator<Item = Result<char, E>>,
+{
+    pub fn new(chars: I) -> Self {
+        Self {
+            chars,
+            detector: Detector::new(),
         }
     }
 
-    unreachable!("Input contains no message marker")
+    /// Only the markers that don't share any character with the previous one
+    pub fn non_overlapping(self) -> impl Iterator<Item = Result<Marker, E>> {
+        let mut next_allowed = 0;
+        self.filter(move |marker| match marker {
+            Ok(marker) if marker.position < next_allowed => false,
+            Ok(marker) => {
+                next_allowed = marker.position + WINDOW;
+                true
+            }
+            Err(_) => true,
+        })
+    }
 }
 
-/// Ring buffer based solution
-pub fn message_start(input: &str) -> usize {
-    const PREV_SIZE: usize = 13;
-    let mut prev = [' '; PREV_SIZE];
-    prev.copy_from_slice(&input.chars().collect::<Vec<_>>()[..PREV_SIZE]);
-    for (ix, c) in input.chars().skip(PREV_SIZE).enumerate() {
-        if !prev.contains(&c) && is_uniq(&prev) {
-            return ix + PREV_SIZE + 1;
-        } else {
-            prev[ix % PREV_SIZE] = c;
+impl<const WINDOW: usize, I, E> Iterator for Markers<WINDOW, I>
+where
+    I: Iterator<Item = Result<char, E>>,
+{
+    type Item = Result<Marker, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for c in self.chars.by_ref() {
+            match c {
+                Ok(c) => {
+                    if let Some(marker) = self.detector.push(c) {
+                        return Some(Ok(marker));
+                    }
+                }
+                Err(e) => return Some(Err(e)),
+            }
         }
+        None
     }
+}
 
-    unreachable!("Input contains no message marker")
+/// Markers of a string
+pub fn markers<const WINDOW: usize>(s: &str) -> impl Iterator<Item = Marker> + '_ {
+    Markers::<WINDOW, _>::new(s.chars().map(Ok::<_, Infallible>)).map(|marker| match marker {
+        Ok(marker) => marker,
+        Err(e) => match e {},
+    })
 }
 
-fn is_uniq(s: &[char]) -> bool {
-    let set = s.iter().collect::<HashSet<_>>();
-    matches!(set.len(), len if len == s.len())
+/// Markers of a string that don't overlap
+pub fn non_overlapping_markers<const WINDOW: usize>(s: &str) -> impl Iterator<Item = Marker> + '_ {
+    Markers::<WINDOW, _>::new(s.chars().map(Ok::<_, Infallible>))
+        .non_overlapping()
+        .map(|marker| match marker {
+            Ok(marker) => marker,
+            Err(e) => match e {},
+        })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    // Markers tests with 4
-    #[test]
-    fn test_marker_index_1() {
-        assert_eq!(
-            get_marker_index("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 4),
-            Some(7)
-        );
-    }
-    #[test]
-    fn test_marker_index_2() {
-        assert_eq!(get_marker_index("bvwbjplbgvbhsrlpgdmjqwftvncz", 4), Some(5));
-    }
-    #[test]
-    fn test_marker_index_3() {
-        assert_eq!(get_marker_index("nppdvjthqldpwncqszvftbrmjlhg", 4), Some(6));
+/// Position of the first marker of a string
+pub fn first_marker<const WINDOW: usize>(s: &str) -> Option<usize> {
+    markers::<WINDOW>(s).next().map(|marker| marker.position)
+}
+
+/// Characters of a UTF-8 byte stream, decoded as they are read
+pub struct Utf8Chars<R> {
+    reader: R,
+}
+
+impl<R: BufRead> Utf8Chars<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader }
     }
-    #[test]
-    fn test_marker_index_4() {
-        assert_eq!(
-            get_marker_index("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 4),
-            Some(10)
-        );
+
+    fn next_byte(&mut self) -> io::Result<Option<u8>> {
+        let byte = self.reader.fill_buf()?.first().copied();
+        if byte.is_some() {
+            self.reader.consume(1);
+        }
+        Ok(byte)
     }
-    #[test]
-    fn test_marker_index_5() {
-        assert_eq!(
-            get_marker_index("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 4),
-            Some(11)
-        );
+
+    fn next_char(&mut self) -> io::Result<Option<char>> {
+        let Some(first) = self.next_byte()? else {
+            return Ok(None);
+        };
+        let len = match first {
+            0x00..=0x7f => 1,
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => 0,
+        };
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "stream is not valid UTF-8");
+        if len == 0 {
+            return Err(invalid());
+        }
+        let mut bytes = [first, 0, 0, 0];
+        for byte in bytes.iter_mut().take(len).skip(1) {
+            *byte = self.next_byte()?.ok_or_else(invalid)?;
+        }
+        std::str::from_utf8(&bytes[..len])
+            .map(|s| s.chars().next())
+            .map_err(|_| invalid())
     }
-    // Without markers
-    #[test]
-    fn test_without_markers() {
-        assert_eq!(get_marker_index("aaaabbbbccccd", 4), None);
+}
+
+impl<R: BufRead> Iterator for Utf8Chars<R> {
+    type Item = io::Result<char>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_char().transpose()
     }
-    // Message tests with 14
+}
+
+/// Markers of a stream, read as it goes without loading it whole
+pub fn read_markers<const WINDOW: usize, R: BufRead>(reader: R) -> Markers<WINDOW, Utf8Chars<R>> {
+    Markers::new(Utf8Chars::new(reader))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: [(&str, usize, usize); 5] = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
+
     #[test]
-    fn test_message_index_1() {
-        assert_eq!(
-            get_marker_index("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 14),
-            Some(19)
-        );
+    fn first_markers() {
+        for (input, packet, message) in SAMPLES {
+            assert_eq!(first_marker::<4>(input), Some(packet), "{input}");
+            assert_eq!(first_marker::<14>(input), Some(message), "{input}");
+        }
     }
+
     #[test]
-    fn test_message_index_2() {
-        assert_eq!(
-            get_marker_index("bvwbjplbgvbhsrlpgdmjqwftvncz", 14),
-            Some(23)
-        );
+    fn without_markers() {
+        assert_eq!(first_marker::<4>("aaaabbbbccccd"), None);
+        assert_eq!(first_marker::<4>("abc"), None);
     }
+
     #[test]
-    fn test_message_index_3() {
-        assert_eq!(
-            get_marker_index("nppdvjthqldpwncqszvftbrmjlhg", 14),
-            Some(23)
-        );
+    fn all_and_non_overlapping() {
+        let all = markers::<3>("abcdaab")
+            .map(|m| m.position)
+            .collect::<Vec<_>>();
+        assert_eq!(all, [3, 4, 5]);
+        let apart = non_overlapping_markers::<3>("abcdaab")
+            .map(|m| m.position)
+            .collect::<Vec<_>>();
+        assert_eq!(apart, [3]);
+        let apart = non_overlapping_markers::<2>("abcab")
+            .map(|m| m.position)
+            .collect::<Vec<_>>();
+        assert_eq!(apart, [2, 4]);
     }
+
     #[test]
-    fn test_message_index_4() {
+    fn non_ascii() {
+        let marker = markers::<4>("ééaéßλ").next().unwrap();
         assert_eq!(
-            get_marker_index("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 14),
-            Some(29)
+            marker,
+            Marker {
+                position: 6,
+                offset: 11
+            }
         );
+        assert_eq!(first_marker::<3>("日本日本語"), Some(5));
+
+        let mut detector = Detector::<3>::new();
+        "aéa".chars().for_each(|c| _ = detector.push(c));
+        assert_eq!(detector.distinct(), 2);
+        detector.push('ü');
+        assert_eq!(detector.distinct(), 3);
     }
+
     #[test]
-    fn test_message_index_5() {
+    fn streams() {
+        for (input, packet, message) in SAMPLES {
+            let mut packets = read_markers::<4, _>(input.as_bytes());
+            assert_eq!(packets.next().unwrap().unwrap().position, packet);
+            let mut messages =
+                read_markers::<14, _>(io::BufReader::with_capacity(3, input.as_bytes()));
+            assert_eq!(messages.next().unwrap().unwrap().position, message);
+        }
+        let marker = read_markers::<2, _>("aéé日".as_bytes())
+            .last()
+            .unwrap()
+            .unwrap();
         assert_eq!(
-            get_marker_index("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 14),
-            Some(26)
+            marker,
+            Marker {
+                position: 4,
+                offset: 8
+            }
         );
+        let error = read_markers::<2, _>(&[b'a', 0xff, b'b'][..])
+            .next()
+            .unwrap();
+        assert_eq!(error.unwrap_err().kind(), io::ErrorKind::InvalidData);
     }
 }
@@ -1,12 +1,16 @@
-use tuning_trouble::{get_marker_index, marker_start, message_start};
+use std::{fs::File, io::BufReader};
 
-fn main() {
-    if let Ok(input) = std::fs::read_to_string("input.txt") {
-        println!("-------------My solution-------------");
-        println!("Signal marker index: {:?}", get_marker_index(&input, 4));
-        println!("Message index: {:?}", get_marker_index(&input, 14));
-        println!("-------------Ring buffer-------------");
-        println!("Signal marker index: {:?}", marker_start(&input));
-        println!("Message index: {:?}", message_start(&input));
-    }
+use tuning_trouble::{read_markers, Marker};
+
+fn first<const WINDOW: usize>() -> std::io::Result<Option<Marker>> {
+    let file = BufReader::new(File::open("input.txt")?);
+    read_markers::<WINDOW, _>(file).next().transpose()
+}
+
+fn main() -> std::io::Result<()> {
+    let packet = first::<4>()?;
+    let message = first::<14>()?;
+    println!("Signal marker index: {:?}", packet.map(|m| m.position));
+    println!("Message index: {:?}", message.map(|m| m.position));
+    Ok(())
 }
@@ -1,108 +1,424 @@
 //! Directory crate
 //! # Purpose
 //! Tree structure representation
-//! of the file system
+//! of the file system, every node living
+//! in a single arena and pointing to the
+//! others by [`NodeId`]
 
-use std::{
-    cell::RefCell,
-    rc::{Rc, Weak},
+use aoc_common::prelude::ParseError;
+
+use crate::{
+    parser::parse_lines,
+    structs::{Command, Entry, Line},
 };
 
+/// Index of a node in its [`FileSystem`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeKind {
+    File,
+    Dir {
+        children: Vec<NodeId>,
+        /// Whether `ls` was run in the directory
+        listed: bool,
+    },
+}
+
 /// Main node struct that represents either Directory,
 /// or File
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Node {
     name: String,
+    parent: Option<NodeId>,
+    kind: NodeKind,
+    /// Size of the file, or of everything below the directory
     size: u64,
-    parent: Weak<RefCell<Node>>,
-    children: Vec<Rc<RefCell<Node>>>,
 }
 
-impl std::fmt::Debug for Node {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Node")
-            .field("name", &self.name)
-            .field("size", &self.size)
-            .field("parent", &self.parent)
-            .field("children", &self.children)
-            .finish()
+impl Node {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    pub fn kind(&self) -> &NodeKind {
+        &self.kind
+    }
+
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, NodeKind::Dir { .. })
+    }
+
+    pub fn children(&self) -> &[NodeId] {
+        match &self.kind {
+            NodeKind::Dir { children, .. } => children,
+            NodeKind::File => &[],
+        }
     }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// Problems found while replaying a transcript, with the 1-based line they
+/// happen on
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum TranscriptError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("line {line}: no directory `{name}` in {path}")]
+    MissingDirectory {
+        line: usize,
+        name: String,
+        path: String,
+    },
+    #[error("line {line}: `cd ..` at the root")]
+    ParentOfRoot { line: usize },
+    #[error("line {line}: {path} was already listed")]
+    DuplicateListing { line: usize, path: String },
+    #[error("line {line}: `{name}` listed twice in {path}")]
+    DuplicateEntry {
+        line: usize,
+        name: String,
+        path: String,
+    },
+    #[error("line {line}: output without an `ls` command")]
+    UnexpectedOutput { line: usize },
 }
 
-impl Default for Node {
+/// File system rebuilt from a terminal transcript
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for FileSystem {
     fn default() -> Self {
         Self {
-            name: "/".to_string(),
-            size: 0,
-            parent: Weak::new(),
-            children: vec![],
+            nodes: vec![Node {
+                name: "/".to_owned(),
+                parent: None,
+                kind: NodeKind::Dir {
+                    children: Vec::new(),
+                    listed: false,
+                },
+                size: 0,
+            }],
         }
     }
 }
 
-#[allow(clippy::new_ret_no_self)]
-impl Node {
-    pub fn new(name: &str, parent: Weak<RefCell<Node>>) -> Self {
-        Self {
-            name: name.to_owned(),
-            parent,
-            size: 0,
-            children: vec![],
+impl FileSystem {
+    pub const ROOT: NodeId = NodeId(0);
+
+    /// Replays the `cd`/`ls` transcript.
+    ///
+    /// Directories can only be entered once they showed up in a listing, and
+    /// every directory can only be listed once.
+    pub fn from_transcript(input: &str) -> Result<Self, TranscriptError> {
+        let mut fs = Self::default();
+        let mut current = Self::ROOT;
+        let mut listing = false;
+
+        for (idx, line) in parse_lines(input).enumerate() {
+            let line_number = idx + 1;
+            match line? {
+                Line::Command(Command::Ls) => {
+                    let path = fs.path(current);
+                    match &mut fs.nodes[current.0].kind {
+                        NodeKind::Dir { listed: true, .. } => {
+                            return Err(TranscriptError::DuplicateListing {
+                                line: line_number,
+                                path,
+                            })
+                        }
+                        NodeKind::Dir { listed, .. } => *listed = true,
+                        NodeKind::File => unreachable!("only directories are entered"),
+                    }
+                    listing = true;
+                }
+                Line::Command(Command::Cd(path)) => {
+                    listing = false;
+                    current = match path.as_str() {
+                        "/" => Self::ROOT,
+                        ".." => fs.nodes[current.0]
+                            .parent
+                            .ok_or(TranscriptError::ParentOfRoot { line: line_number })?,
+                        name => fs.child_dir(current, name).ok_or_else(|| {
+                            TranscriptError::MissingDirectory {
+                                line: line_number,
+                                name: name.to_owned(),
+                                path: fs.path(current),
+                            }
+                        })?,
+                    };
+                }
+                Line::Entry(entry) => {
+                    if !listing {
+                        return Err(TranscriptError::UnexpectedOutput { line: line_number });
+                    }
+                    let (name, kind, size) = match entry {
+                        Entry::Dir(name) => (
+                            name,
+                            NodeKind::Dir {
+                                children: Vec::new(),
+                                listed: false,
+                            },
+                            0,
+                        ),
+                        Entry::File(size, name) => (name, NodeKind::File, size),
+                    };
+                    if fs.child(current, &name).is_some() {
+                        return Err(TranscriptError::DuplicateEntry {
+                            line: line_number,
+                            name,
+                            path: fs.path(current),
+                        });
+                    }
+                    fs.push(current, name, kind, size);
+                }
+            }
         }
+
+        fs.compute_sizes();
+        Ok(fs)
     }
 
-    pub fn name(&self) -> &str {
-        &self.name
+    fn push(&mut self, parent: NodeId, name: String, kind: NodeKind, size: u64) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            name,
+            parent: Some(parent),
+            kind,
+            size,
+        });
+        if let NodeKind::Dir { children, .. } = &mut self.nodes[parent.0].kind {
+            children.push(id);
+        }
+        id
     }
 
-    pub fn parent(&self) -> &Weak<RefCell<Node>> {
-        &self.parent
+    /// Children are always created after their parent, so walking the arena
+    /// backwards sees every node before its parent
+    fn compute_sizes(&mut self) {
+        for idx in (1..self.nodes.len()).rev() {
+            let (size, parent) = (self.nodes[idx].size, self.nodes[idx].parent);
+            if let Some(parent) = parent {
+                self.nodes[parent.0].size += size;
+            }
+        }
     }
 
-    pub fn set_size(&mut self, size: u64) {
-        self.size = size;
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
     }
 
-    pub fn children_mut(&mut self) -> &mut Vec<Rc<RefCell<Node>>> {
-        &mut self.children
+    /// Every node, the root first and children after their parent
+    pub fn ids(&self) -> impl Iterator<Item = NodeId> {
+        (0..self.nodes.len()).map(NodeId)
     }
 
-    pub fn children(&self) -> &Vec<Rc<RefCell<Node>>> {
-        &self.children
+    pub fn directories(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.ids().filter(|&id| self.node(id).is_dir())
     }
 
-    pub fn is_dir(&self) -> bool {
-        self.size != 0 && self.children.is_empty()
+    pub fn files(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.ids().filter(|&id| !self.node(id).is_dir())
     }
 
-    pub fn size(&self) -> u64 {
-        let children_size = self
-            .children
+    pub fn size(&self, id: NodeId) -> u64 {
+        self.node(id).size
+    }
+
+    pub fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        self.node(dir)
+            .children()
             .iter()
-            .map(|child| child.borrow().size())
-            .sum::<u64>();
-        self.size + children_size
+            .copied()
+            .find(|&child| self.node(child).name == name)
     }
-}
 
-pub struct PrettyNode<'a>(pub &'a Rc<RefCell<Node>>);
+    fn child_dir(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        self.child(dir, name)
+            .filter(|&child| self.node(child).is_dir())
+    }
 
-impl<'a> std::fmt::Debug for PrettyNode<'a> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let this = self.0.borrow();
-        if this.size == 0 {
-            writeln!(f, "(dir)")?;
-        } else {
-            writeln!(f, "(file size={})", this.size)?;
+    /// Absolute path of the node, `/` for the root
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = Vec::new();
+        let mut current = id;
+        while let Some(parent) = self.node(current).parent {
+            names.push(self.node(current).name.as_str());
+            current = parent;
         }
-        for child in &this.children {
-            for (index, line) in format!("{:?}", PrettyNode(child)).lines().enumerate() {
-                if index == 0 {
-                    writeln!(f, "{} {}", child.borrow().name, line)?;
-                } else {
-                    writeln!(f, "  {line}")?;
-                }
-            }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Node at an absolute path such as `/a/e/i`
+    pub fn lookup(&self, path: &str) -> Option<NodeId> {
+        let relative = path.strip_prefix('/')?;
+        relative
+            .split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(Self::ROOT, |dir, name| self.child(dir, name))
+    }
+
+    /// Nodes matching `predicate`, in creation order
+    pub fn find<'a, P>(&'a self, mut predicate: P) -> impl Iterator<Item = NodeId> + 'a
+    where
+        P: FnMut(&Node) -> bool + 'a,
+    {
+        self.ids().filter(move |&id| predicate(self.node(id)))
+    }
+
+    pub fn find_by_name<'a>(&'a self, name: &'a str) -> impl Iterator<Item = NodeId> + 'a {
+        self.find(move |node| node.name == name)
+    }
+
+    /// Directories whose total size lies in `range`
+    pub fn find_dirs_by_size<R>(&self, range: R) -> impl Iterator<Item = NodeId> + '_
+    where
+        R: std::ops::RangeBounds<u64> + 'static,
+    {
+        self.find(move |node| node.is_dir() && range.contains(&node.size))
+    }
+
+    /// Bytes to delete so that a disk of `disk` bytes has `needed` bytes
+    /// unused
+    pub fn missing_space(&self, disk: u64, needed: u64) -> u64 {
+        let unused = disk.saturating_sub(self.size(Self::ROOT));
+        needed.saturating_sub(unused)
+    }
+
+    /// Smallest directory to delete to get `needed` bytes unused on a disk of
+    /// `disk` bytes, `None` when no directory is big enough.
+    ///
+    /// When nothing is missing, the smallest directory is returned.
+    pub fn dir_to_delete(&self, disk: u64, needed: u64) -> Option<NodeId> {
+        self.find_dirs_by_size(self.missing_space(disk, needed)..)
+            .min_by_key(|&id| self.size(id))
+    }
+
+    /// Nodes below `id`, `id` included, parents before their children
+    pub fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+        let mut stack = vec![id];
+        let mut nodes = Vec::new();
+        while let Some(id) = stack.pop() {
+            nodes.push(id);
+            stack.extend(self.node(id).children().iter().rev());
+        }
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = include_str!("../examples/sample.txt");
+
+    #[test]
+    fn sizes_and_paths() {
+        let fs = FileSystem::from_transcript(SAMPLE).unwrap();
+        assert_eq!(fs.size(FileSystem::ROOT), 48381165);
+
+        let e = fs.lookup("/a/e").unwrap();
+        assert_eq!(fs.size(e), 584);
+        assert_eq!(fs.path(e), "/a/e");
+        assert_eq!(fs.lookup("/"), Some(FileSystem::ROOT));
+        assert_eq!(fs.lookup("/a/e/"), Some(e));
+        assert_eq!(fs.lookup("/a/x"), None);
+        assert_eq!(fs.lookup("a"), None);
+
+        let i = fs.lookup("/a/e/i").unwrap();
+        assert!(!fs.node(i).is_dir());
+        assert_eq!(fs.node(i).parent(), Some(e));
+        assert!(fs.node(e).is_dir());
+    }
+
+    #[test]
+    fn empty_directory_is_a_dir() {
+        let fs = FileSystem::from_transcript("$ cd /\n$ ls\ndir empty\n0 zero").unwrap();
+        assert!(fs.node(fs.lookup("/empty").unwrap()).is_dir());
+        assert!(!fs.node(fs.lookup("/zero").unwrap()).is_dir());
+    }
+
+    #[test]
+    fn queries() {
+        let fs = FileSystem::from_transcript(SAMPLE).unwrap();
+        let small = fs
+            .find_dirs_by_size(..=100_000)
+            .map(|id| fs.path(id))
+            .collect::<Vec<_>>();
+        assert_eq!(small, ["/a", "/a/e"]);
+        let named = fs
+            .find_by_name("d")
+            .map(|id| fs.path(id))
+            .collect::<Vec<_>>();
+        assert_eq!(named, ["/d"]);
+        let big_files = fs
+            .find(|node| !node.is_dir() && node.size() > 8_000_000)
+            .count();
+        assert_eq!(big_files, 3);
+    }
+
+    #[test]
+    fn transcript_errors() {
+        assert_eq!(
+            FileSystem::from_transcript("$ cd /\n$ ls\ndir a\n$ cd b"),
+            Err(TranscriptError::MissingDirectory {
+                line: 4,
+                name: "b".to_owned(),
+                path: "/".to_owned()
+            })
+        );
+        assert_eq!(
+            FileSystem::from_transcript("$ cd /\n$ ls\n12 a\n$ cd a"),
+            Err(TranscriptError::MissingDirectory {
+                line: 4,
+                name: "a".to_owned(),
+                path: "/".to_owned()
+            })
+        );
+        assert_eq!(
+            FileSystem::from_transcript("$ cd /\n$ cd .."),
+            Err(TranscriptError::ParentOfRoot { line: 2 })
+        );
+        assert_eq!(
+            FileSystem::from_transcript("$ ls\n1 a\n$ ls\n1 a"),
+            Err(TranscriptError::DuplicateListing {
+                line: 3,
+                path: "/".to_owned()
+            })
+        );
+        assert_eq!(
+            FileSystem::from_transcript("$ ls\n1 a\n2 a"),
+            Err(TranscriptError::DuplicateEntry {
+                line: 3,
+                name: "a".to_owned(),
+                path: "/".to_owned()
+            })
+        );
+        assert_eq!(
+            FileSystem::from_transcript("$ cd /\n1 a"),
+            Err(TranscriptError::UnexpectedOutput { line: 2 })
+        );
+        match FileSystem::from_transcript("$ cd /\n$ rm a") {
+            Err(TranscriptError::Parse(e)) => assert_eq!(e.line, 2),
+            other => panic!("expected a parse error, got {other:?}"),
         }
-        Ok(())
     }
 }
@@ -0,0 +1,239 @@
+//! Explore crate
+//! # Purpose
+//! Interactive walk through a [`FileSystem`]
+//! rebuilt from a transcript
+
+use std::{
+    fmt::Write as _,
+    io::{self, BufRead, Write},
+};
+
+use aoc_common::prelude::ParseError;
+
+use crate::{
+    directory::{FileSystem, NodeId},
+    parser::parse_query_line,
+    report,
+    structs::{Command, Query, SizeFilter},
+    DISK_SIZE,
+};
+
+const HELP: &str = "\
+cd DIR | cd .. | cd /    change directory, DIR may be an absolute path
+ls                       list the current directory
+pwd                      print the current directory
+du [DEPTH]               sizes of the directories below the current one
+find -size [+|-]N        files bigger than, smaller than or exactly N bytes
+free NEEDED [DISK]       smallest directory to delete to get NEEDED bytes unused
+help                     this help
+quit                     leave
+";
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ExploreError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("cd: {0}: no such directory")]
+    NoSuchDirectory(String),
+}
+
+/// What to do after a line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    Output(String),
+    Quit,
+}
+
+/// Current directory in a file system, and the commands moving around it
+#[derive(Debug, Clone)]
+pub struct Session<'a> {
+    fs: &'a FileSystem,
+    cwd: NodeId,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(fs: &'a FileSystem) -> Self {
+        Self {
+            fs,
+            cwd: FileSystem::ROOT,
+        }
+    }
+
+    pub fn cwd(&self) -> NodeId {
+        self.cwd
+    }
+
+    fn cd(&mut self, path: &str) -> Result<(), ExploreError> {
+        let target = match path {
+            "/" => Some(FileSystem::ROOT),
+            ".." => Some(self.fs.node(self.cwd).parent().unwrap_or(FileSystem::ROOT)),
+            path if path.starts_with('/') => self.fs.lookup(path),
+            path => {
+                path.split('/')
+                    .filter(|name| !name.is_empty())
+                    .try_fold(self.cwd, |dir, name| match name {
+                        ".." => self.fs.node(dir).parent().or(Some(dir)),
+                        name => self.fs.child(dir, name),
+                    })
+            }
+        };
+        match target.filter(|&id| self.fs.node(id).is_dir()) {
+            Some(id) => {
+                self.cwd = id;
+                Ok(())
+            }
+            None => Err(ExploreError::NoSuchDirectory(path.to_owned())),
+        }
+    }
+
+    fn ls(&self) -> String {
+        self.fs
+            .node(self.cwd)
+            .children()
+            .iter()
+            .fold(String::new(), |mut out, &child| {
+                let child = self.fs.node(child);
+                let _ = match child.is_dir() {
+                    true => writeln!(out, "dir {}", child.name()),
+                    false => writeln!(out, "{} {}", child.size(), child.name()),
+                };
+                out
+            })
+    }
+
+    fn find(&self, query: SizeFilter) -> String {
+        self.fs
+            .descendants(self.cwd)
+            .into_iter()
+            .filter(|&id| !self.fs.node(id).is_dir() && query.matches(self.fs.size(id)))
+            .fold(String::new(), |mut out, id| {
+                let _ = writeln!(out, "{}", self.fs.path(id));
+                out
+            })
+    }
+
+    fn free(&self, needed: u64, disk: u64) -> String {
+        let missing = self.fs.missing_space(disk, needed);
+        if missing == 0 {
+            return format!("{needed} bytes are already unused, nothing to delete\n");
+        }
+        match self.fs.dir_to_delete(disk, needed) {
+            Some(id) => format!(
+                "delete {} ({} bytes) to free the missing {missing} bytes\n",
+                self.fs.path(id),
+                self.fs.size(id)
+            ),
+            None => format!("no directory frees the missing {missing} bytes\n"),
+        }
+    }
+
+    /// Runs one line typed by the user
+    pub fn execute(&mut self, line: &str) -> Result<Reply, ExploreError> {
+        if line.trim().is_empty() {
+            return Ok(Reply::Output(String::new()));
+        }
+        let output = match parse_query_line(line)? {
+            Query::Command(Command::Cd(path)) => {
+                self.cd(path.trim())?;
+                String::new()
+            }
+            Query::Command(Command::Ls) => self.ls(),
+            Query::Pwd => format!("{}\n", self.fs.path(self.cwd)),
+            Query::Du(depth) => report::du(self.fs, self.cwd, depth, true),
+            Query::Find(filter) => self.find(filter),
+            Query::Free { needed, disk } => self.free(needed, disk.unwrap_or(DISK_SIZE)),
+            Query::Help => HELP.to_owned(),
+            Query::Quit => return Ok(Reply::Quit),
+        };
+        Ok(Reply::Output(output))
+    }
+
+    /// Reads commands from `input` until it ends or `quit`, writing a prompt
+    /// before every line, and the replies and errors to `output`
+    pub fn repl<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        write!(output, "{}> ", self.fs.path(self.cwd))?;
+        output.flush()?;
+        for line in input.lines() {
+            match self.execute(&line?) {
+                Ok(Reply::Output(text)) => write!(output, "{text}")?,
+                Ok(Reply::Quit) => return Ok(()),
+                Err(e) => writeln!(output, "{e}")?,
+            }
+            write!(output, "{}> ", self.fs.path(self.cwd))?;
+            output.flush()?;
+        }
+        writeln!(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(session: &mut Session, line: &str) -> String {
+        match session.execute(line).unwrap() {
+            Reply::Output(text) => text,
+            Reply::Quit => panic!("unexpected quit"),
+        }
+    }
+
+    #[test]
+    fn moving_around() {
+        let fs = FileSystem::from_transcript(include_str!("../examples/sample.txt")).unwrap();
+        let mut session = Session::new(&fs);
+        assert_eq!(run(&mut session, "$ cd a"), "");
+        assert_eq!(run(&mut session, "pwd"), "/a\n");
+        assert_eq!(
+            run(&mut session, "ls"),
+            "dir e\n29116 f\n2557 g\n62596 h.lst\n"
+        );
+        assert_eq!(run(&mut session, "cd e/../e"), "");
+        assert_eq!(run(&mut session, "pwd"), "/a/e\n");
+        assert_eq!(run(&mut session, "cd /d"), "");
+        assert_eq!(run(&mut session, "cd .."), "");
+        assert_eq!(run(&mut session, "pwd"), "/\n");
+        assert_eq!(
+            session.execute("cd b.txt"),
+            Err(ExploreError::NoSuchDirectory("b.txt".to_owned()))
+        );
+        assert!(matches!(
+            session.execute("rm -rf /"),
+            Err(ExploreError::Parse(_))
+        ));
+        assert_eq!(session.execute("quit"), Ok(Reply::Quit));
+    }
+
+    #[test]
+    fn queries() {
+        let fs = FileSystem::from_transcript(include_str!("../examples/sample.txt")).unwrap();
+        let mut session = Session::new(&fs);
+        assert_eq!(
+            run(&mut session, "find -size +8000000"),
+            "/b.txt\n/c.dat\n/d/d.log\n"
+        );
+        assert_eq!(run(&mut session, "find -size -3000"), "/a/e/i\n/a/g\n");
+        assert_eq!(run(&mut session, "du 1"), "47M\t/\n24M\t/d\n93K\t/a\n");
+        assert_eq!(
+            run(&mut session, "free 30000000"),
+            "delete /d (24933642 bytes) to free the missing 8381165 bytes\n"
+        );
+        assert_eq!(
+            run(&mut session, "free 100 50000000"),
+            "100 bytes are already unused, nothing to delete\n"
+        );
+        run(&mut session, "cd a");
+        assert_eq!(run(&mut session, "find -size 584"), "/a/e/i\n");
+    }
+
+    #[test]
+    fn repl_session() {
+        let fs = FileSystem::from_transcript(include_str!("../examples/sample.txt")).unwrap();
+        let mut output = Vec::new();
+        Session::new(&fs)
+            .repl("cd a\nbogus\npwd\nquit\nls\n".as_bytes(), &mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("/> /a> line 1, column 1"), "{output}");
+        assert!(output.ends_with("/a> /a\n/a> "), "{output}");
+    }
+}
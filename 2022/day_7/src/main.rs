@@ -1,7 +1,47 @@
-use no_space_left_on_device::{part_1, part_2};
+use no_space_left_on_device::{part_1, part_2, report, FileSystem};
+
+const USAGE: &str = "usage: no_space_left_on_device [tree | du [max-depth] | json | transcript]";
 
 fn main() {
-    let input = include_str!("../input.txt");
-    println!("Part 1 result: {}", part_1(input));
-    println!("Part 2 result: {}", part_2(input));
+    let input = &aoc_common::puzzle_input!("../input.txt");
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    if args.is_empty() {
+        match (part_1(input), part_2(input)) {
+            (Ok(part_1), Ok(part_2)) => {
+                println!("Part 1 result: {part_1}");
+                println!("Part 2 result: {part_2}");
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let fs = match FileSystem::from_transcript(input) {
+        Ok(fs) => fs,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        ["tree"] => print!("{}", report::tree(&fs, FileSystem::ROOT)),
+        ["du"] => print!("{}", report::du(&fs, FileSystem::ROOT, None, true)),
+        ["du", depth] => match depth.parse() {
+            Ok(depth) => print!("{}", report::du(&fs, FileSystem::ROOT, Some(depth), true)),
+            Err(_) => {
+                eprintln!("{USAGE}");
+                std::process::exit(2);
+            }
+        },
+        ["json"] => println!("{}", report::json(&fs)),
+        ["transcript"] => print!("{}", report::transcript(&fs)),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    }
 }
@@ -1,13 +1,15 @@
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    character::complete::{multispace1, not_line_ending},
-    combinator::{all_consuming, map},
-    sequence::{preceded, separated_pair, terminated},
-    Finish, IResult,
+    character::complete::{multispace1, not_line_ending, space0, space1, u64 as size},
+    combinator::{map, opt, value},
+    sequence::{preceded, separated_pair, terminated, tuple},
+    IResult,
 };
 
-use crate::structs::{Cd, Command, Entry, Line, Ls};
+use aoc_common::prelude::{run, ParseError};
+
+use crate::structs::{Cd, Command, Entry, Line, Ls, Query, SizeFilter};
 
 fn parse_ls(input: &str) -> IResult<&str, Ls> {
     map(tag("ls"), |_| Ls)(input)
@@ -20,7 +22,7 @@ fn parse_cd(input: &str) -> IResult<&str, Cd> {
     )(input)
 }
 
-fn parse_command(input: &str) -> IResult<&str, Command> {
+pub(crate) fn parse_command(input: &str) -> IResult<&str, Command> {
     let shell_line_begin = terminated(tag("$"), multispace1);
     let cmd_ls = map(parse_ls, Into::into);
     let cmd_cd = map(parse_cd, Into::into);
@@ -35,7 +37,7 @@ fn parse_entry(input: &str) -> IResult<&str, Entry> {
     );
     let file = map(
         separated_pair(
-            nom::character::complete::u64,
+            size,
             tag(" "),
             map(not_line_ending, |p: &str| p.to_string()),
         ),
@@ -52,13 +54,60 @@ fn parse_line(input: &str) -> IResult<&str, Line> {
     ))(input)
 }
 
+fn parse_size_filter(input: &str) -> IResult<&str, SizeFilter> {
+    alt((
+        map(preceded(tag("+"), size), SizeFilter::Above),
+        map(preceded(tag("-"), size), SizeFilter::Below),
+        map(size, SizeFilter::Exactly),
+    ))(input)
+}
+
+/// Line typed in the explorer, `cd` and `ls` going
+/// through the same grammar as the transcript
+fn parse_query(input: &str) -> IResult<&str, Query> {
+    let number = || preceded(space1, size);
+    terminated(
+        alt((
+            map(parse_command, Query::Command),
+            map(
+                alt((map(parse_ls, Command::from), map(parse_cd, Command::from))),
+                Query::Command,
+            ),
+            value(Query::Pwd, tag("pwd")),
+            map(preceded(tag("du"), opt(number())), |depth| {
+                Query::Du(depth.map(|depth| depth as usize))
+            }),
+            map(
+                preceded(
+                    tuple((tag("find"), space1, tag("-size"), space1)),
+                    parse_size_filter,
+                ),
+                Query::Find,
+            ),
+            map(
+                preceded(tag("free"), tuple((number(), opt(number())))),
+                |(needed, disk)| Query::Free { needed, disk },
+            ),
+            value(Query::Help, alt((tag("help"), tag("?")))),
+            value(Query::Quit, alt((tag("quit"), tag("exit")))),
+        )),
+        space0,
+    )(input)
+}
+
+/// Reads one explorer line
+pub fn parse_query_line(input: &str) -> Result<Query, ParseError> {
+    run(parse_query, input.trim())
+}
+
 /// High level function to turn `&str` input
 /// into the Iterator of `parser::Line` items
-/// which could be either `Command` or `Entry`
-pub fn get_parsed_lines(input: &str) -> impl Iterator<Item = Line> + '_ {
+/// which could be either `Command` or `Entry`,
+/// errors being located in the whole input
+pub fn parse_lines(input: &str) -> impl Iterator<Item = Result<Line, ParseError>> + '_ {
     input
         .lines()
-        .map(|l| all_consuming(parse_line)(l).finish().unwrap().1)
+        .map(|l| run(parse_line, l).map_err(|e| e.within(input, l)))
 }
 
 #[cfg(test)]
@@ -72,10 +121,7 @@ mod tests {
 
     #[test]
     fn test_cd() {
-        assert_eq!(
-            parse_cd("cd /home\r\n"),
-            Ok(("\r\n", Cd("/home".to_string())))
-        );
+        assert_eq!(parse_cd("cd /home\n"), Ok(("\n", Cd("/home".to_string()))));
     }
 
     #[test]
@@ -141,13 +187,16 @@ mod tests {
     }
 
     #[test]
-    fn test_get_parsed_lines() {
+    fn test_parse_lines() {
         let input = "$ ls\ndir directory_name";
         let expected = vec![
             Line::Command(Command::Ls),
             Line::Entry(Entry::Dir("directory_name".to_string())),
         ];
-        let parsed_lines: Vec<Line> = get_parsed_lines(input).collect();
+        let parsed_lines: Vec<Line> = parse_lines(input).collect::<Result<_, _>>().unwrap();
         assert_eq!(parsed_lines, expected);
+
+        let error = parse_lines("$ ls\n$ rm x").nth(1).unwrap().unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
     }
 }
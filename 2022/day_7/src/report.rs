@@ -0,0 +1,277 @@
+//! Report crate
+//! # Purpose
+//! Different ways to print a [`FileSystem`]:
+//! `tree`, `du`, JSON, and back to a transcript
+
+use std::fmt::Write;
+
+use crate::directory::{FileSystem, NodeId, NodeKind};
+
+/// `tree`-like listing of `id` and everything below it, with sizes.
+///
+/// Directory names end with `/`.
+pub fn tree(fs: &FileSystem, id: NodeId) -> String {
+    let mut out = format!("{}\n", label(fs, id));
+    tree_children(fs, id, "", &mut out);
+    out
+}
+
+fn label(fs: &FileSystem, id: NodeId) -> String {
+    let node = fs.node(id);
+    match (node.is_dir(), node.parent()) {
+        (true, None) => format!("/ ({})", node.size()),
+        (true, Some(_)) => format!("{}/ ({})", node.name(), node.size()),
+        (false, _) => format!("{} ({})", node.name(), node.size()),
+    }
+}
+
+fn tree_children(fs: &FileSystem, id: NodeId, prefix: &str, out: &mut String) {
+    let children = fs.node(id).children();
+    for (idx, &child) in children.iter().enumerate() {
+        let last = idx + 1 == children.len();
+        let (branch, indent) = match last {
+            true => ("└── ", "    "),
+            false => ("├── ", "│   "),
+        };
+        out.push_str(&format!("{prefix}{branch}{}\n", label(fs, child)));
+        tree_children(fs, child, &format!("{prefix}{indent}"), out);
+    }
+}
+
+/// Size the way `du -h` prints it: bytes up to 1023, then the unit prefix
+/// with one rounded up decimal below 10 and none above
+pub fn human_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+    if size < 1024 {
+        return size.to_string();
+    }
+    let mut value = size as f64;
+    for unit in UNITS {
+        value /= 1024.0;
+        if value < 1024.0 || unit == "E" {
+            return match value < 10.0 {
+                true => {
+                    let rounded = (value * 10.0).ceil() / 10.0;
+                    match rounded < 10.0 {
+                        true => format!("{rounded:.1}{unit}"),
+                        false => format!("10{unit}"),
+                    }
+                }
+                false => format!("{}{unit}", value.ceil()),
+            };
+        }
+    }
+    unreachable!("the last unit always returns")
+}
+
+/// `du --max-depth N` over `id` and the directories below it, biggest first.
+///
+/// `id` is at depth 0, `None` shows every directory and `human` prints sizes
+/// like `du -h`.
+pub fn du(fs: &FileSystem, id: NodeId, max_depth: Option<usize>, human: bool) -> String {
+    let base = depth(fs, id);
+    let mut entries = fs
+        .descendants(id)
+        .into_iter()
+        .filter(|&id| fs.node(id).is_dir())
+        .filter(|&id| max_depth.is_none_or(|max| depth(fs, id) - base <= max))
+        .map(|id| (fs.size(id), fs.path(id)))
+        .collect::<Vec<_>>();
+    entries.sort_by(|(a_size, a_path), (b_size, b_path)| {
+        b_size.cmp(a_size).then_with(|| a_path.cmp(b_path))
+    });
+
+    entries
+        .into_iter()
+        .fold(String::new(), |mut out, (size, path)| {
+            let size = match human {
+                true => human_size(size),
+                false => size.to_string(),
+            };
+            let _ = writeln!(out, "{size}\t{path}");
+            out
+        })
+}
+
+fn depth(fs: &FileSystem, id: NodeId) -> usize {
+    std::iter::successors(fs.node(id).parent(), |&parent| fs.node(parent).parent()).count()
+}
+
+/// The whole file system as a JSON object, directories having their entries
+/// under `children`
+pub fn json(fs: &FileSystem) -> String {
+    let mut out = String::new();
+    json_node(fs, FileSystem::ROOT, &mut out);
+    out
+}
+
+fn json_node(fs: &FileSystem, id: NodeId, out: &mut String) {
+    let node = fs.node(id);
+    let _ = write!(out, "{{\"name\":");
+    json_string(node.name(), out);
+    match node.is_dir() {
+        true => {
+            let _ = write!(
+                out,
+                ",\"type\":\"dir\",\"size\":{},\"children\":[",
+                node.size()
+            );
+            for (idx, &child) in node.children().iter().enumerate() {
+                if idx > 0 {
+                    out.push(',');
+                }
+                json_node(fs, child, out);
+            }
+            out.push_str("]}");
+        }
+        false => {
+            let _ = write!(out, ",\"type\":\"file\",\"size\":{}}}", node.size());
+        }
+    }
+}
+
+fn json_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Shortest `$ cd`/`$ ls` transcript rebuilding the same file system.
+///
+/// Directories are visited depth first, in listing order, and only the ones
+/// that were listed get an `ls`.
+pub fn transcript(fs: &FileSystem) -> String {
+    let mut lines = vec!["$ cd /".to_owned()];
+    transcript_dir(fs, FileSystem::ROOT, &mut lines);
+    while lines.last().is_some_and(|line| line == "$ cd ..") {
+        lines.pop();
+    }
+    lines.iter().fold(String::new(), |mut out, line| {
+        let _ = writeln!(out, "{line}");
+        out
+    })
+}
+
+fn transcript_dir(fs: &FileSystem, id: NodeId, lines: &mut Vec<String>) {
+    let node = fs.node(id);
+    if let NodeKind::Dir { listed: false, .. } = node.kind() {
+        return;
+    }
+    lines.push("$ ls".to_owned());
+    for &child in node.children() {
+        let child = fs.node(child);
+        lines.push(match child.is_dir() {
+            true => format!("dir {}", child.name()),
+            false => format!("{} {}", child.size(), child.name()),
+        });
+    }
+    for &child in node.children() {
+        if let NodeKind::Dir { listed: true, .. } = fs.node(child).kind() {
+            lines.push(format!("$ cd {}", fs.node(child).name()));
+            transcript_dir(fs, child, lines);
+            lines.push("$ cd ..".to_owned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = include_str!("../examples/sample.txt");
+
+    #[test]
+    fn tree_listing() {
+        let fs = FileSystem::from_transcript(SAMPLE).unwrap();
+        let expected = "\
+/ (48381165)
+├── a/ (94853)
+│   ├── e/ (584)
+│   │   └── i (584)
+│   ├── f (29116)
+│   ├── g (2557)
+│   └── h.lst (62596)
+├── b.txt (14848514)
+├── c.dat (8504156)
+└── d/ (24933642)
+    ├── j (4060174)
+    ├── d.log (8033020)
+    ├── d.ext (5626152)
+    └── k (7214296)
+";
+        assert_eq!(tree(&fs, FileSystem::ROOT), expected);
+        assert_eq!(
+            tree(&fs, fs.lookup("/a/e").unwrap()),
+            "e/ (584)\n└── i (584)\n"
+        );
+    }
+
+    #[test]
+    fn du_output() {
+        let fs = FileSystem::from_transcript(SAMPLE).unwrap();
+        assert_eq!(
+            du(&fs, FileSystem::ROOT, Some(1), true),
+            "47M\t/\n24M\t/d\n93K\t/a\n"
+        );
+        assert_eq!(
+            du(&fs, FileSystem::ROOT, None, false),
+            "48381165\t/\n24933642\t/d\n94853\t/a\n584\t/a/e\n"
+        );
+        assert_eq!(du(&fs, FileSystem::ROOT, Some(0), false), "48381165\t/\n");
+        let a = fs.lookup("/a").unwrap();
+        assert_eq!(du(&fs, a, Some(0), false), "94853\t/a\n");
+    }
+
+    #[test]
+    fn human_sizes() {
+        assert_eq!(human_size(0), "0");
+        assert_eq!(human_size(1023), "1023");
+        assert_eq!(human_size(1024), "1.0K");
+        assert_eq!(human_size(1025), "1.1K");
+        assert_eq!(human_size(10 * 1024 - 1), "10K");
+        assert_eq!(human_size(10 * 1024 + 1), "11K");
+        assert_eq!(human_size(5 * 1024 * 1024 * 1024), "5.0G");
+    }
+
+    #[test]
+    fn json_export() {
+        let fs = FileSystem::from_transcript("$ cd /\n$ ls\ndir a\n3 \"q\".txt\n$ cd a\n$ ls\n")
+            .unwrap();
+        assert_eq!(
+            json(&fs),
+            r#"{"name":"/","type":"dir","size":3,"children":[{"name":"a","type":"dir","size":0,"children":[]},{"name":"\"q\".txt","type":"file","size":3}]}"#
+        );
+    }
+
+    #[test]
+    fn transcript_round_trip() {
+        let fs = FileSystem::from_transcript(SAMPLE).unwrap();
+        let written = transcript(&fs);
+        assert_eq!(written, format!("{SAMPLE}\n"));
+
+        let reparsed = FileSystem::from_transcript(&written).unwrap();
+        assert_eq!(reparsed, fs);
+        assert_eq!(transcript(&reparsed), written);
+    }
+
+    #[test]
+    fn unlisted_directories_stay_unlisted() {
+        let input = "$ cd /\n$ ls\ndir a\ndir b\n$ cd b\n$ ls\n1 x\n$ cd /\n$ cd a\n";
+        let fs = FileSystem::from_transcript(input).unwrap();
+        let written = transcript(&fs);
+        assert_eq!(written, "$ cd /\n$ ls\ndir a\ndir b\n$ cd b\n$ ls\n1 x\n");
+        assert_eq!(FileSystem::from_transcript(&written).unwrap(), fs);
+    }
+}
@@ -10,7 +10,7 @@ pub struct Cd(pub String);
 
 /// Enum with all the available commands in this task
 /// `ls` and `cd`
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Command {
     Ls,
     Cd(String),
@@ -44,3 +44,43 @@ pub enum Line {
     Command(Command),
     Entry(Entry),
 }
+
+/// Size comparison of `find -size`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeFilter {
+    /// `+N`
+    Above(u64),
+    /// `-N`
+    Below(u64),
+    /// `N`
+    Exactly(u64),
+}
+
+impl SizeFilter {
+    pub fn matches(self, size: u64) -> bool {
+        match self {
+            SizeFilter::Above(limit) => size > limit,
+            SizeFilter::Below(limit) => size < limit,
+            SizeFilter::Exactly(limit) => size == limit,
+        }
+    }
+}
+
+/// Each line typed in the explorer, the transcript
+/// commands being allowed with or without `$ `
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Command(Command),
+    Pwd,
+    /// `du [max-depth]`
+    Du(Option<usize>),
+    /// `find -size [+-]N`
+    Find(SizeFilter),
+    /// `free NEEDED [DISK]`
+    Free {
+        needed: u64,
+        disk: Option<u64>,
+    },
+    Help,
+    Quit,
+}
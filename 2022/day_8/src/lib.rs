@@ -1,37 +1,30 @@
-use crate::tree::Forest;
+pub use crate::{
+    scan::{Direction, Survey, Viewpoint},
+    tree::Forest,
+};
 
-mod tree;
+pub mod scan;
+pub mod tree;
 
 pub fn part_1(input: &str) -> usize {
-    let grid = Forest::build(input);
-    // dbg!(&grid);
-    // dbg!(PrettyVisibilityGrid(&grid.get_visibility_grid()));
-    let result = grid
-        .get_visibility_grid()
-        .iter()
-        .flatten()
-        .filter(|&b| b == &true)
-        .count();
-    // println!("Total number of visible trees on the map: {}", result);
-    result
+    Survey::new(&Forest::build(input), &Direction::ORTHOGONAL).visible_count()
 }
 
 pub fn part_2(input: &str) -> usize {
-    let grid = Forest::build(input);
-    // dbg!(&grid);
-    // dbg!(PrettyVisibilityGrid(&grid.get_visibility_grid()));
-    grid.get_score_grid()
-        .into_iter()
-        .flatten()
-        .max()
-        .unwrap_or_default()
-    // println!("Total number of visible trees on the map: {}", result);
+    Survey::new(&Forest::build(input), &Direction::ORTHOGONAL)
+        .best_viewpoint()
+        .map_or(0, |view| view.score())
 }
 
 #[cfg(test)]
 mod tests {
     pub use super::*;
 
+    aoc_macros::examples! {
+        part1: part_1,
+        part2: part_2,
+    }
+
     const TEST_INPUT: &str = "30373
 25512
 65332
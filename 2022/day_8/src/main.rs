@@ -1,9 +1,20 @@
-use treetop_tree_house::{part_1, part_2};
+use treetop_tree_house::{part_1, part_2, Direction, Forest, Survey};
 
 fn main() {
-    let input = include_str!("../input.txt");
+    let input = &aoc_common::puzzle_input!("../input.txt");
     println!("How many trees are visible from outside the grid?");
     println!("Part 1 answer: {:?}", part_1(input));
     println!();
     println!("Part 2 answer: {:?}", part_2(input));
+
+    let survey = Survey::new(&Forest::build(input), &Direction::ALL);
+    if let Some(best) = survey.best_viewpoint() {
+        println!();
+        println!(
+            "Looking diagonally too, the best tree is at row {}, column {} with a score of {}",
+            best.row,
+            best.col,
+            best.score()
+        );
+    }
 }
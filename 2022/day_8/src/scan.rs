@@ -0,0 +1,304 @@
+//! Visibility and scenic scores with one monotonic stack per line of sight,
+//! `O(rows·cols)` for every direction.
+
+use crate::tree::{Forest, ScoreGrid, VisibilityGrid};
+
+/// Step between two neighbouring trees of a line of sight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Direction {
+    pub rows: isize,
+    pub cols: isize,
+}
+
+impl Direction {
+    pub const UP: Self = Self::new(-1, 0);
+    pub const DOWN: Self = Self::new(1, 0);
+    pub const LEFT: Self = Self::new(0, -1);
+    pub const RIGHT: Self = Self::new(0, 1);
+    pub const UP_LEFT: Self = Self::new(-1, -1);
+    pub const UP_RIGHT: Self = Self::new(-1, 1);
+    pub const DOWN_LEFT: Self = Self::new(1, -1);
+    pub const DOWN_RIGHT: Self = Self::new(1, 1);
+
+    /// The four directions of the puzzle
+    pub const ORTHOGONAL: [Self; 4] = [Self::UP, Self::LEFT, Self::RIGHT, Self::DOWN];
+    /// The orthogonal directions and the diagonals
+    pub const ALL: [Self; 8] = [
+        Self::UP_LEFT,
+        Self::UP,
+        Self::UP_RIGHT,
+        Self::LEFT,
+        Self::RIGHT,
+        Self::DOWN_LEFT,
+        Self::DOWN,
+        Self::DOWN_RIGHT,
+    ];
+
+    /// # Panics
+    ///
+    /// If both steps are 0, such a direction never leaves the tree.
+    pub const fn new(rows: isize, cols: isize) -> Self {
+        assert!(rows != 0 || cols != 0, "direction without any step");
+        Self { rows, cols }
+    }
+}
+
+/// What can be seen from one tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Viewpoint {
+    pub row: usize,
+    pub col: usize,
+    /// Whether the tree can be seen from outside the forest
+    pub visible: bool,
+    /// Viewing distance in every direction, in the order they were given
+    pub distances: Vec<usize>,
+}
+
+impl Viewpoint {
+    /// Trees seen from the viewpoint, the blocking ones included
+    pub fn visible_trees(&self) -> usize {
+        self.distances.iter().sum()
+    }
+
+    /// Product of the viewing distances
+    pub fn score(&self) -> usize {
+        self.distances.iter().product()
+    }
+}
+
+/// Visibility and viewing distances of every tree of a forest for a set of
+/// directions
+#[derive(Debug, Clone)]
+pub struct Survey {
+    rows: usize,
+    cols: usize,
+    directions: Vec<Direction>,
+    visible: Vec<bool>,
+    /// One grid of viewing distances per direction, row by row
+    distances: Vec<Vec<usize>>,
+}
+
+impl Survey {
+    pub fn new(forest: &Forest, directions: &[Direction]) -> Self {
+        let (rows, cols) = (forest.rows(), forest.cols());
+        let mut survey = Self {
+            rows,
+            cols,
+            directions: directions.to_vec(),
+            visible: vec![false; rows * cols],
+            distances: Vec::with_capacity(directions.len()),
+        };
+        for &direction in directions {
+            let distances = survey.scan(forest, direction);
+            survey.distances.push(distances);
+        }
+        survey
+    }
+
+    fn step(&self, (row, col): (usize, usize), by: Direction) -> Option<(usize, usize)> {
+        let row = row.checked_add_signed(by.rows).filter(|&r| r < self.rows)?;
+        let col = col.checked_add_signed(by.cols).filter(|&c| c < self.cols)?;
+        Some((row, col))
+    }
+
+    /// Walks every line of sight from its far end in `direction` back to its
+    /// start, keeping a stack of the trees already walked that are not hidden
+    /// behind a taller one. The trees popped are shorter than the current
+    /// one, what stays on top blocks the view.
+    fn scan(&mut self, forest: &Forest, direction: Direction) -> Vec<usize> {
+        let back = Direction::new(-direction.rows, -direction.cols);
+        let mut distances = vec![0; self.rows * self.cols];
+        let mut stack: Vec<(u32, usize)> = Vec::new();
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                // Only start from trees at the edge in `direction`
+                if self.step((row, col), direction).is_some() {
+                    continue;
+                }
+                stack.clear();
+                let mut cell = Some((row, col));
+                let mut index = 0;
+                while let Some((r, c)) = cell {
+                    let height = forest.height(r, c);
+                    while stack.last().is_some_and(|&(h, _)| h < height) {
+                        stack.pop();
+                    }
+                    distances[r * self.cols + c] = match stack.last() {
+                        Some(&(_, blocker)) => index - blocker,
+                        None => {
+                            self.visible[r * self.cols + c] = true;
+                            index
+                        }
+                    };
+                    stack.push((height, index));
+                    index += 1;
+                    cell = self.step((r, c), back);
+                }
+            }
+        }
+        distances
+    }
+
+    pub fn directions(&self) -> &[Direction] {
+        &self.directions
+    }
+
+    pub fn is_visible(&self, row: usize, col: usize) -> bool {
+        row < self.rows && col < self.cols && self.visible[row * self.cols + col]
+    }
+
+    /// Trees that can be seen from outside the forest
+    pub fn visible_count(&self) -> usize {
+        self.visible.iter().filter(|&&v| v).count()
+    }
+
+    /// What can be seen from the tree at `row`, `col`
+    pub fn viewpoint(&self, row: usize, col: usize) -> Option<Viewpoint> {
+        if row >= self.rows || col >= self.cols {
+            return None;
+        }
+        let idx = row * self.cols + col;
+        Some(Viewpoint {
+            row,
+            col,
+            visible: self.visible[idx],
+            distances: self.distances.iter().map(|grid| grid[idx]).collect(),
+        })
+    }
+
+    pub fn score(&self, row: usize, col: usize) -> Option<usize> {
+        self.viewpoint(row, col).map(|view| view.score())
+    }
+
+    /// Viewpoint with the highest scenic score, the first one on ties
+    pub fn best_viewpoint(&self) -> Option<Viewpoint> {
+        (0..self.rows * self.cols)
+            .map(|idx| {
+                (
+                    idx,
+                    self.distances
+                        .iter()
+                        .map(|grid| grid[idx])
+                        .product::<usize>(),
+                )
+            })
+            .reduce(|best, current| if current.1 > best.1 { current } else { best })
+            .and_then(|(idx, _)| self.viewpoint(idx / self.cols, idx % self.cols))
+    }
+
+    pub fn visibility_grid(&self) -> VisibilityGrid {
+        self.visible
+            .chunks(self.cols.max(1))
+            .map(<[bool]>::to_vec)
+            .collect()
+    }
+
+    pub fn score_grid(&self) -> ScoreGrid {
+        (0..self.rows)
+            .map(|row| {
+                (0..self.cols)
+                    .map(|col| self.score(row, col).unwrap_or_default())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = include_str!("../examples/sample.txt");
+
+    /// Small xorshift, enough to get varied forests without a dependency
+    fn random_forest(seed: u64, rows: usize, cols: usize) -> Forest {
+        let mut state = seed.max(1);
+        let mut text = String::new();
+        for _ in 0..rows {
+            for _ in 0..cols {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                text.push(char::from(b'0' + (state % 10) as u8));
+            }
+            text.push('\n');
+        }
+        Forest::build(&text)
+    }
+
+    /// Walks every direction from every tree
+    fn brute_force(forest: &Forest, directions: &[Direction]) -> (VisibilityGrid, ScoreGrid) {
+        let (rows, cols) = (forest.rows() as isize, forest.cols() as isize);
+        let mut visible = vec![vec![false; cols as usize]; rows as usize];
+        let mut scores = vec![vec![1; cols as usize]; rows as usize];
+        for row in 0..rows {
+            for col in 0..cols {
+                let height = forest.height(row as usize, col as usize);
+                for d in directions {
+                    let (mut r, mut c, mut distance) = (row + d.rows, col + d.cols, 0);
+                    let mut blocked = false;
+                    while (0..rows).contains(&r) && (0..cols).contains(&c) {
+                        distance += 1;
+                        if forest.height(r as usize, c as usize) >= height {
+                            blocked = true;
+                            break;
+                        }
+                        r += d.rows;
+                        c += d.cols;
+                    }
+                    visible[row as usize][col as usize] |= !blocked;
+                    scores[row as usize][col as usize] *= distance;
+                }
+            }
+        }
+        (visible, scores)
+    }
+
+    #[test]
+    fn matches_reference_implementation() {
+        let mut forests = vec![Forest::build(SAMPLE)];
+        forests.extend((1..20).map(|seed| random_forest(seed, 3 + seed as usize % 7, 11)));
+        for forest in forests {
+            let survey = Survey::new(&forest, &Direction::ORTHOGONAL);
+            assert_eq!(
+                survey.visibility_grid(),
+                forest.get_visibility_grid(),
+                "{forest}"
+            );
+            assert_eq!(survey.score_grid(), forest.get_score_grid(), "{forest}");
+        }
+    }
+
+    #[test]
+    fn any_directions() {
+        let directions = [Direction::ALL.as_slice(), &[Direction::new(1, 2)]].concat();
+        for seed in 1..20 {
+            let forest = random_forest(seed, 9, 3 + seed as usize % 5);
+            let survey = Survey::new(&forest, &directions);
+            let (visible, scores) = brute_force(&forest, &directions);
+            assert_eq!(survey.visibility_grid(), visible, "{forest}");
+            assert_eq!(survey.score_grid(), scores, "{forest}");
+        }
+    }
+
+    #[test]
+    fn viewpoints() {
+        let forest = Forest::build(SAMPLE);
+        let survey = Survey::new(&forest, &Direction::ORTHOGONAL);
+        assert_eq!(survey.visible_count(), 21);
+
+        let best = survey.best_viewpoint().unwrap();
+        assert_eq!((best.row, best.col, best.score()), (3, 2, 8));
+        // up, left, right, down
+        assert_eq!(best.distances, [2, 2, 2, 1]);
+        assert_eq!(best.visible_trees(), 7);
+
+        let view = survey.viewpoint(1, 2).unwrap();
+        assert_eq!((view.score(), view.visible), (4, true));
+        assert_eq!(survey.viewpoint(5, 0), None);
+
+        let diagonal = Survey::new(&forest, &Direction::ALL);
+        assert_eq!(diagonal.viewpoint(3, 2).unwrap().visible_trees(), 12);
+    }
+}
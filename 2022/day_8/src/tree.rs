@@ -34,6 +34,22 @@ pub struct Forest {
 }
 
 impl Forest {
+    pub fn rows(&self) -> usize {
+        self.trees.len()
+    }
+
+    /// Width of the first row, rows being expected to all have the same
+    pub fn cols(&self) -> usize {
+        self.trees.first().map_or(0, Vec::len)
+    }
+
+    /// # Panics
+    ///
+    /// If the tree is outside of the forest.
+    pub fn height(&self, row: usize, col: usize) -> u32 {
+        self.trees[row][col].0
+    }
+
     pub fn build(s: &str) -> Forest {
         let mut grid: TreeGrid = vec![];
 
@@ -47,6 +63,7 @@ impl Forest {
         Self { trees: grid }
     }
 
+    /// Reference implementation, walking every direction from every tree
     pub fn get_visibility_grid(&self) -> VisibilityGrid {
         let mut visibility: VisibilityGrid = vec![];
 
@@ -64,6 +81,7 @@ impl Forest {
         visibility
     }
 
+    /// Reference implementation, walking every direction from every tree
     pub fn get_score_grid(&self) -> ScoreGrid {
         let mut score_grid: ScoreGrid = vec![];
 
@@ -85,10 +103,10 @@ impl Forest {
             .collect::<Vec<_>>();
         let current_tree = row_vec.get(col)?;
 
-        if row == row_vec.len() - 1 || row == 0 {
+        if row == col_vec.len() - 1 || row == 0 {
             return Some(0);
         }
-        if col == col_vec.len() - 1 || col == 0 {
+        if col == row_vec.len() - 1 || col == 0 {
             return Some(0);
         }
 
@@ -136,10 +154,10 @@ impl Forest {
         let col_vec = TreeColIter::new(grid, col).collect::<Vec<_>>();
         let current_tree = row_vec.get(col)?;
 
-        if row == row_vec.len() - 1 || row == 0 {
+        if row == col_vec.len() - 1 || row == 0 {
             return Some(true);
         }
-        if col == col_vec.len() - 1 || col == 0 {
+        if col == row_vec.len() - 1 || col == 0 {
             return Some(true);
         }
 
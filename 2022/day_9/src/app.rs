@@ -1,69 +1,81 @@
-use std::collections::VecDeque;
+use egui::plot::{Line, PlotPoints, Points};
 
-use egui::plot::Points;
-
-use crate::{
-    command::{Command, Direction},
-    rope::{Position, Rope},
-};
+use crate::{command::Direction, replay::Replay, rope::Position};
 
+/// Plays a [`Replay`] back, forward or backward, one frame at a time
 pub struct RopeApp {
-    commands: VecDeque<Command>,
-    rope: Rope,
+    replay: Replay,
+    /// Frame on screen
+    cursor: usize,
+    playing: bool,
+    backward: bool,
     timer: std::time::Instant,
     update_interval: std::time::Duration,
 }
 
-impl Default for RopeApp {
-    fn default() -> Self {
-        let commands = Command::get_commands(
-            std::fs::read_to_string("input.txt")
-                .unwrap_or_default() // will get empty string on Err
-                .as_str(),
-        )
-        .unwrap_or_default()
-        .into();
+impl RopeApp {
+    /// Called once before the first frame.
+    pub fn new(_cc: &eframe::CreationContext<'_>, replay: Replay) -> Self {
         Self {
-            commands,
-            rope: Rope::new(10),
+            replay,
+            cursor: 0,
+            playing: false,
+            backward: false,
             timer: std::time::Instant::now(),
             update_interval: std::time::Duration::from_millis(250u64),
         }
     }
-}
 
-impl RopeApp {
-    /// Called once before the first frame.
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Default::default()
+    fn last_frame(&self) -> usize {
+        self.replay.frame_count() - 1
     }
 
-    fn plot(&self, ui: &mut egui::Ui) -> egui::Response {
-        use egui::plot::PlotPoints;
+    fn knots(&self) -> &[Position] {
+        self.replay.frame(self.cursor).unwrap_or_default()
+    }
 
-        let plot_points: PlotPoints = self
-            .rope
-            .tail_visits()
+    fn plot(&self, ui: &mut egui::Ui) -> egui::Response {
+        let to_point = |position: &Position| [position.x as f64, position.y as f64];
+
+        let tail_points = self
+            .replay
+            .knot_count()
+            .checked_sub(1)
+            .map(|tail| self.replay.visits_until(tail, self.cursor))
+            .unwrap_or_default()
             .iter()
-            .map(|tail_visit| [tail_visit.x as f64, tail_visit.y as f64])
-            .collect();
-
-        let tail_points = Points::new(plot_points)
+            .map(to_point)
+            .collect::<PlotPoints>();
+        let tail_points = Points::new(tail_points)
             .radius(5.0)
             .shape(egui::plot::MarkerShape::Square)
             .color(egui::Color32::GREEN)
-            .name("Tail");
-
-        let plot_points: PlotPoints = PlotPoints::new(vec![[
-            self.rope.head().unwrap_or(&Position::default()).x as f64,
-            self.rope.head().unwrap_or(&Position::default()).y as f64,
-        ]]);
-
-        let head_point = Points::new(plot_points)
-            .radius(5.0)
-            .shape(egui::plot::MarkerShape::Diamond)
-            .color(egui::Color32::RED)
-            .name("Head");
+            .name("Tail visits");
+
+        let rope_line = Line::new(self.knots().iter().map(to_point).collect::<PlotPoints>())
+            .color(egui::Color32::GRAY)
+            .name("Rope");
+        let knot_points = Points::new(
+            self.knots()
+                .iter()
+                .skip(1)
+                .map(to_point)
+                .collect::<PlotPoints>(),
+        )
+        .radius(4.0)
+        .color(egui::Color32::LIGHT_GRAY)
+        .name("Knots");
+        let head_point = Points::new(
+            self.knots()
+                .iter()
+                .take(1)
+                .map(to_point)
+                .collect::<PlotPoints>(),
+        )
+        .radius(5.0)
+        .shape(egui::plot::MarkerShape::Diamond)
+        .color(egui::Color32::RED)
+        .name("Head");
 
         egui::plot::Plot::new("example_plot")
             .center_x_axis(true)
@@ -74,17 +86,33 @@ impl RopeApp {
             .legend(egui::plot::Legend::default())
             .show(ui, |plot_ui| {
                 plot_ui.points(tail_points);
+                plot_ui.line(rope_line);
+                plot_ui.points(knot_points);
                 plot_ui.points(head_point);
             })
             .response
     }
 
-    fn timed_command_process(&mut self) {
+    fn step_forward(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.last_frame());
+    }
+
+    fn step_backward(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    fn timed_step(&mut self) {
         // Update by timer
-        if self.timer.elapsed() >= self.update_interval {
-            if let Some(cmd) = self.commands.pop_front() {
-                self.rope.process_command(cmd);
+        if self.playing && self.timer.elapsed() >= self.update_interval {
+            match self.backward {
+                true => self.step_backward(),
+                false => self.step_forward(),
             }
+            let at_end = match self.backward {
+                true => self.cursor == 0,
+                false => self.cursor == self.last_frame(),
+            };
+            self.playing &= !at_end;
             self.timer = std::time::Instant::now();
         }
     }
@@ -97,14 +125,46 @@ impl RopeApp {
         }
         res
     }
+
+    fn controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("⏮").clicked() {
+                self.cursor = 0;
+            }
+            if ui.button("⏴").clicked() {
+                self.step_backward();
+            }
+            if ui.button("◀").clicked() {
+                self.playing = true;
+                self.backward = true;
+            }
+            if ui.button("⏸").clicked() {
+                self.playing = false;
+            }
+            if ui.button("▶").clicked() {
+                self.playing = true;
+                self.backward = false;
+            }
+            if ui.button("⏵").clicked() {
+                self.step_forward();
+            }
+            if ui.button("⏭").clicked() {
+                self.cursor = self.last_frame();
+            }
+        });
+        let last = self.last_frame();
+        ui.add(egui::Slider::new(&mut self.cursor, 0..=last).text("step"));
+    }
 }
 
 impl eframe::App for RopeApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // Auto update
-        ctx.request_repaint_after(self.update_interval);
+        if self.playing {
+            ctx.request_repaint_after(self.update_interval);
+        }
         // Update by timer
-        self.timed_command_process();
+        self.timed_step();
 
         #[cfg(not(target_arch = "wasm32"))] // no File->Quit on web pages!
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
@@ -120,51 +180,62 @@ impl eframe::App for RopeApp {
 
         egui::SidePanel::left("side_panel").show(ctx, |ui| {
             ui.heading("Commands:");
-
-            if ui.button("Process command").clicked() {
-                if let Some(cmd) = self.commands.pop_front() {
-                    self.rope.process_command(cmd);
-                }
-            }
             ui.separator();
+            let current = self.replay.command_of(self.cursor);
+            let mut jump = None;
             egui::ScrollArea::vertical().show(ui, |ui| {
-                self.commands.iter().for_each(|cmd| {
+                for (idx, cmd) in self.replay.commands().iter().enumerate() {
                     let arrow = match cmd.direction() {
                         Direction::Up => "⬆",
                         Direction::Down => "⬇",
                         Direction::Right => "➡",
                         Direction::Left => "⬅",
+                        Direction::UpLeft => "⬉",
+                        Direction::UpRight => "⬈",
+                        Direction::DownLeft => "⬋",
+                        Direction::DownRight => "⬊",
                     };
-                    ui.label(arrow.repeat(cmd.steps() as usize));
-                });
+                    let label = ui
+                        .selectable_label(current == Some(idx), arrow.repeat(cmd.steps() as usize));
+                    if label.clicked() {
+                        jump = Some(idx);
+                    }
+                }
             });
+            if let Some(idx) = jump {
+                self.cursor = self.replay.frame_of(idx);
+            }
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("Rope plot");
             ui.separator();
+            self.controls(ui);
             ui.horizontal(|ui| {
                 self.integer_edit_field(ui);
                 ui.label(self.timer.elapsed().as_millis().to_string());
             });
             ui.separator();
-            ui.label(&format!(
-                "Total tail visits:{}",
-                self.rope.tail_visits_count()
-            ));
+            let tail_visits = self
+                .replay
+                .knot_count()
+                .checked_sub(1)
+                .map_or(0, |tail| self.replay.visits_until(tail, self.cursor).len());
+            ui.label(&format!("Total tail visits:{tail_visits}"));
             self.plot(ui);
             egui::warn_if_debug_build(ui);
         });
     }
 }
 
-pub fn run_gui() -> eframe::Result<()> {
+/// Opens a window playing `replay`
+pub fn run_gui(replay: Replay) -> eframe::Result<()> {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
     let native_options = eframe::NativeOptions::default();
     eframe::run_native(
         "Rope simulation",
         native_options,
-        Box::new(|cc| Box::new(RopeApp::new(cc))),
+        Box::new(|cc| Box::new(RopeApp::new(cc, replay))),
     )
 }
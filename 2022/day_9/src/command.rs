@@ -1,38 +1,62 @@
 mod error;
 
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
-use crate::command::error::CommandError;
+pub use crate::command::error::CommandError;
 
+use aoc_common::prelude::{lines_with, run};
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::digit1,
     combinator::{map, map_res, value},
     sequence::separated_pair,
-    Finish, IResult,
+    IResult,
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 
 impl Direction {
     fn parse_direction(input: &str) -> IResult<&str, Direction> {
+        // Diagonals first, they start with the same letters
         alt((
+            value(Direction::UpLeft, tag("UL")),
+            value(Direction::UpRight, tag("UR")),
+            value(Direction::DownLeft, tag("DL")),
+            value(Direction::DownRight, tag("DR")),
             value(Direction::Up, tag("U")),
             value(Direction::Down, tag("D")),
             value(Direction::Left, tag("L")),
             value(Direction::Right, tag("R")),
         ))(input)
     }
+
+    /// One step in this direction, `y` going up
+    pub fn offset(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, 1),
+            Direction::Down => (0, -1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, 1),
+            Direction::UpRight => (1, 1),
+            Direction::DownLeft => (-1, -1),
+            Direction::DownRight => (1, -1),
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Command {
     direction: Direction,
     steps: u32,
@@ -40,6 +64,10 @@ pub struct Command {
 
 /// main impl block
 impl Command {
+    pub fn new(direction: Direction, steps: u32) -> Self {
+        Self { direction, steps }
+    }
+
     pub fn direction(&self) -> Direction {
         self.direction
     }
@@ -49,21 +77,40 @@ impl Command {
     }
 }
 
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Direction::Up => "U",
+            Direction::Down => "D",
+            Direction::Left => "L",
+            Direction::Right => "R",
+            Direction::UpLeft => "UL",
+            Direction::UpRight => "UR",
+            Direction::DownLeft => "DL",
+            Direction::DownRight => "DR",
+        })
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.direction, self.steps)
+    }
+}
+
 impl FromStr for Command {
     type Err = CommandError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match Command::parse_command(s).finish() {
-            Ok((_remaining, command)) => Ok(command),
-            Err(e) => Err(e.into()),
-        }
+        Ok(run(Command::parse_command, s)?)
     }
 }
 
 /// nom Parsing impl block
 impl Command {
+    /// One command per line, errors pointing at the line and column
     pub fn get_commands(input: &str) -> Result<Vec<Command>, CommandError> {
-        input.lines().map(Command::from_str).collect()
+        Ok(lines_with(input, |line| run(Command::parse_command, line))?)
     }
 
     fn parse_steps(input: &str) -> IResult<&str, u32> {
@@ -76,3 +123,31 @@ impl Command {
         )(input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diagonal_commands() {
+        let commands = Command::get_commands("UL 2\nD 3\nDR 1").unwrap();
+        assert_eq!(
+            commands,
+            [
+                Command::new(Direction::UpLeft, 2),
+                Command::new(Direction::Down, 3),
+                Command::new(Direction::DownRight, 1),
+            ]
+        );
+        assert_eq!(commands[0].to_string(), "UL 2");
+        assert_eq!("UL 2".parse(), Ok(commands[0]));
+    }
+
+    #[test]
+    fn errors_have_a_position() {
+        let CommandError::Parse(error) = Command::get_commands("R 4\nU 4\nX 3").unwrap_err();
+        assert_eq!((error.line, error.column), (3, 1));
+        let CommandError::Parse(error) = Command::get_commands("R 4\nU 4x").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+}
@@ -1,63 +1,76 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
+#[cfg(feature = "gui")]
 mod app;
-mod command;
-mod rope;
+pub mod command;
+pub mod replay;
+pub mod rope;
 
+#[cfg(feature = "gui")]
 pub use app::run_gui;
 
-pub fn part_1(input: &str) -> usize {
-    let mut rope = rope::Rope::new(2);
-    let commands =
-        command::Command::get_commands(input).expect("expected all commands to be parsed");
-    commands
+use crate::{
+    command::{Command, CommandError},
+    rope::Rope,
+};
+
+/// Runs every command of `input` on `rope`
+pub fn simulate(rope: &mut Rope, input: &str) -> Result<(), CommandError> {
+    Command::get_commands(input)?
         .into_iter()
         .for_each(|cmd| rope.process_command(cmd));
-    rope.tail_visits_count()
+    Ok(())
 }
 
-pub fn part_2(input: &str) -> usize {
-    let mut rope = rope::Rope::new(10);
-    let commands =
-        command::Command::get_commands(input).expect("expected all commands to be parsed");
-    commands
-        .into_iter()
-        .for_each(|cmd| rope.process_command(cmd));
-    rope.tail_visits_count()
+pub fn part_1(input: &str) -> Result<usize, CommandError> {
+    let mut rope = Rope::new(2);
+    simulate(&mut rope, input)?;
+    Ok(rope.tail_visits_count())
+}
+
+pub fn part_2(input: &str) -> Result<usize, CommandError> {
+    let mut rope = Rope::new(10);
+    simulate(&mut rope, input)?;
+    Ok(rope.tail_visits_count())
 }
 
 #[cfg(test)]
 mod tests {
     pub use super::*;
 
-    const TEST_INPUT: &str = "R 4
-U 4
-L 3
-D 1
-R 4
-D 1
-L 5
-R 2";
-
-    const TEST_INPUT_LONG_MOVE: &str = "R 5
-U 8
-L 8
-D 3
-R 17
-D 10
-L 25
-U 20
-";
+    aoc_macros::examples! {
+        part1: |input| part_1(input).unwrap(),
+        part2: |input| part_2(input).unwrap(),
+    }
+
+    const TEST_INPUT: &str = include_str!("../examples/sample.txt");
+
+    const TEST_INPUT_LONG_MOVE: &str = include_str!("../examples/larger.txt");
 
     #[test]
     fn test_part_1() {
-        assert_eq!(part_1(TEST_INPUT), 13);
-        assert_eq!(part_1(TEST_INPUT_LONG_MOVE), 88);
+        assert_eq!(part_1(TEST_INPUT), Ok(13));
+        assert_eq!(part_1(TEST_INPUT_LONG_MOVE), Ok(88));
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part_2(TEST_INPUT), 1);
-        assert_eq!(part_2(TEST_INPUT_LONG_MOVE), 36);
+        assert_eq!(part_2(TEST_INPUT), Ok(1));
+        assert_eq!(part_2(TEST_INPUT_LONG_MOVE), Ok(36));
+    }
+
+    #[test]
+    fn every_knot_is_tracked() {
+        let mut rope = Rope::new(10);
+        simulate(&mut rope, TEST_INPUT_LONG_MOVE).unwrap();
+        assert_eq!(rope.visit_count(1), 88);
+        assert_eq!(rope.visit_count(9), 36);
+        let tail = rope.bounds(9).unwrap();
+        assert_eq!((tail.width(), tail.height()), (22, 12));
+    }
+
+    #[test]
+    fn errors_are_returned() {
+        assert!(part_1("R 4\nQ 2").is_err());
     }
 }
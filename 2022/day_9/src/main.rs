@@ -1,13 +1,72 @@
-use rope_bridge::{part_1, part_2};
+use rope_bridge::{part_1, part_2, replay::Replay};
+
+const USAGE: &str = "usage: rope_bridge [--gui | csv | json | svg] [knots]";
+
+/// Seconds each step lasts in the SVG animation
+const SVG_FRAME_SECONDS: f64 = 0.05;
 
 fn main() {
-    let input = include_str!("../input.txt");
-    println!("How many positions does the tail of the rope visit at least once?");
-    println!("Part 1 answer: {:?}", part_1(input));
-    println!("Part 2 answer: {:?}", part_2(input));
-
-    if let Err(e) = rope_bridge::run_gui() {
-        eprintln!("{e}");
-        std::process::exit(1);
+    let input = &aoc_common::puzzle_input!("../input.txt");
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let (mode, knots) = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        [] => (None, 10),
+        [mode] => (Some(mode), 10),
+        [mode, knots] => match knots.parse() {
+            Ok(knots) => (Some(mode), knots),
+            Err(_) => {
+                eprintln!("{USAGE}");
+                std::process::exit(2);
+            }
+        },
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
+    if matches!(mode, None | Some("--gui")) {
+        println!("How many positions does the tail of the rope visit at least once?");
+        match (part_1(input), part_2(input)) {
+            (Ok(part_1), Ok(part_2)) => {
+                println!("Part 1 answer: {part_1}");
+                println!("Part 2 answer: {part_2}");
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+    }
+    let Some(mode) = mode else {
+        return;
+    };
+
+    let replay = match Replay::from_input(knots, input) {
+        Ok(replay) => replay,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    match mode {
+        #[cfg(feature = "gui")]
+        "--gui" => {
+            if let Err(e) = rope_bridge::run_gui(replay) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        #[cfg(not(feature = "gui"))]
+        "--gui" => {
+            eprintln!("rope_bridge was built without the `gui` feature");
+            std::process::exit(2);
+        }
+        "csv" => print!("{}", replay.to_csv()),
+        "json" => println!("{}", replay.to_json()),
+        "svg" => print!("{}", replay.to_svg(SVG_FRAME_SECONDS)),
+        _ => {
+            eprintln!("{USAGE}");
+            std::process::exit(2);
+        }
     }
 }
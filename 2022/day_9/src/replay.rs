@@ -0,0 +1,301 @@
+//! Replay crate
+//! # Purpose
+//! Every position of every knot, step by step,
+//! and the CSV, JSON and SVG exports of that timeline
+
+use std::{collections::HashSet, fmt::Write};
+
+use crate::{
+    command::{Command, CommandError},
+    rope::{Bounds, Position, Rope},
+};
+
+/// Size of a grid cell in the SVG export, in pixels
+const SVG_CELL: u32 = 8;
+
+/// Positions of the knots of a rope after every single step of its commands
+#[derive(Debug, Clone)]
+pub struct Replay {
+    knots: usize,
+    commands: Vec<Command>,
+    /// Knot positions of every frame one after the other, `knots` per frame,
+    /// the starting one first
+    positions: Vec<Position>,
+    /// Index in `commands` of the command that led to each frame, none for
+    /// the first one
+    steps: Vec<Option<usize>>,
+}
+
+impl Replay {
+    /// Plays `commands` on `rope`, keeping every frame
+    pub fn record(mut rope: Rope, commands: Vec<Command>) -> Self {
+        let knots = rope.knots().len();
+        let mut positions = rope.knots().to_vec();
+        let mut steps = vec![None];
+        for (idx, cmd) in commands.iter().enumerate() {
+            for _ in 0..cmd.steps() {
+                rope.step(cmd.direction());
+                positions.extend_from_slice(rope.knots());
+                steps.push(Some(idx));
+            }
+        }
+        Self {
+            knots,
+            commands,
+            positions,
+            steps,
+        }
+    }
+
+    /// Replay of the commands of `input` on a rope of `knots` knots
+    pub fn from_input(knots: usize, input: &str) -> Result<Self, CommandError> {
+        Ok(Self::record(
+            Rope::new(knots),
+            Command::get_commands(input)?,
+        ))
+    }
+
+    pub fn knot_count(&self) -> usize {
+        self.knots
+    }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Frames in the replay, the starting one included
+    pub fn frame_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Knot positions at `frame`, the head first
+    pub fn frame(&self, frame: usize) -> Option<&[Position]> {
+        let start = frame.checked_mul(self.knots)?;
+        self.positions.get(start..start + self.knots)
+    }
+
+    /// Index of the command that led to `frame`
+    pub fn command_of(&self, frame: usize) -> Option<usize> {
+        self.steps.get(frame).copied().flatten()
+    }
+
+    /// First frame of the command at index `command`
+    pub fn frame_of(&self, command: usize) -> usize {
+        self.steps
+            .partition_point(|&step| step < Some(command))
+            .min(self.frame_count() - 1)
+    }
+
+    /// Positions of `knot` in every frame
+    pub fn track(&self, knot: usize) -> impl Iterator<Item = Position> + '_ {
+        (0..self.frame_count())
+            .filter_map(move |frame| self.frame(frame).and_then(|knots| knots.get(knot)))
+            .copied()
+    }
+
+    /// Positions `knot` visited up to `frame`, both included
+    pub fn visits_until(&self, knot: usize, frame: usize) -> HashSet<Position> {
+        self.track(knot).take(frame.saturating_add(1)).collect()
+    }
+
+    /// Area the whole rope moved in
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::around(self.positions.iter().copied())
+    }
+
+    /// One `frame,command,knot,x,y` row per knot and frame, the command column
+    /// being empty for the starting frame
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("frame,command,knot,x,y\n");
+        for frame in 0..self.frame_count() {
+            let command = self
+                .command_of(frame)
+                .map(|idx| self.commands[idx].to_string())
+                .unwrap_or_default();
+            for (knot, position) in self.frame(frame).unwrap_or_default().iter().enumerate() {
+                let _ = writeln!(
+                    out,
+                    "{frame},{command},{knot},{},{}",
+                    position.x, position.y
+                );
+            }
+        }
+        out
+    }
+
+    /// The commands, and every frame as the command that led to it and the
+    /// `[x, y]` of its knots
+    pub fn to_json(&self) -> String {
+        let commands = self
+            .commands
+            .iter()
+            .map(|cmd| format!("\"{cmd}\""))
+            .collect::<Vec<_>>()
+            .join(",");
+        let frames = (0..self.frame_count())
+            .map(|frame| {
+                let command = self
+                    .command_of(frame)
+                    .map_or("null".to_owned(), |idx| idx.to_string());
+                let knots = self
+                    .frame(frame)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|position| format!("[{},{}]", position.x, position.y))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("{{\"command\":{command},\"knots\":[{knots}]}}")
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"knots\":{},\"commands\":[{commands}],\"frames\":[{frames}]}}",
+            self.knots
+        )
+    }
+
+    /// Animated SVG looping over the frames, `frame_seconds` each.
+    ///
+    /// The head is red, the tail green and the knots between grey. Cells the
+    /// tail visited light up as it reaches them.
+    pub fn to_svg(&self, frame_seconds: f64) -> String {
+        let Some(bounds) = self.bounds() else {
+            return String::new();
+        };
+        let (width, height) = (bounds.width(), bounds.height());
+        let duration = frame_seconds * self.frame_count() as f64;
+        // SVG `y` goes down
+        let column = |position: Position| position.x - bounds.min.x;
+        let row = |position: Position| bounds.max.y - position.y;
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {width} {height}">"#,
+            width * SVG_CELL,
+            height * SVG_CELL
+        );
+        let _ = writeln!(
+            out,
+            r##"<rect width="100%" height="100%" fill="#0f0f23"/>"##
+        );
+
+        if let Some(tail) = self.knots.checked_sub(1) {
+            let mut first_visits = Vec::new();
+            let mut seen = HashSet::new();
+            for (frame, position) in self.track(tail).enumerate() {
+                if seen.insert(position) {
+                    first_visits.push((frame, position));
+                }
+            }
+            for (frame, position) in first_visits {
+                let _ = writeln!(
+                    out,
+                    r##"<rect x="{}" y="{}" width="1" height="1" fill="#2e7d32" opacity="0"><animate attributeName="opacity" values="0;1" keyTimes="0;{}" dur="{duration}s" calcMode="discrete" repeatCount="indefinite"/></rect>"##,
+                    column(position),
+                    row(position),
+                    frame as f64 / self.frame_count() as f64
+                );
+            }
+        }
+
+        for knot in (0..self.knots).rev() {
+            let color = match knot {
+                0 => "#e53935",
+                knot if knot + 1 == self.knots => "#66bb6a",
+                _ => "#9e9e9e",
+            };
+            let values = |coordinate: &dyn Fn(Position) -> i32| {
+                self.track(knot)
+                    .map(|position| format!("{}.5", coordinate(position)))
+                    .collect::<Vec<_>>()
+                    .join(";")
+            };
+            let _ = writeln!(
+                out,
+                r#"<circle r="0.45" fill="{color}"><animate attributeName="cx" values="{}" dur="{duration}s" calcMode="discrete" repeatCount="indefinite"/><animate attributeName="cy" values="{}" dur="{duration}s" calcMode="discrete" repeatCount="indefinite"/></circle>"#,
+                values(&column),
+                values(&row)
+            );
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn short() -> Replay {
+        Replay::from_input(2, "R 2\nU 1").unwrap()
+    }
+
+    #[test]
+    fn frames() {
+        let replay = short();
+        assert_eq!(replay.frame_count(), 4);
+        assert_eq!(replay.frame(0), Some(&[(0, 0).into(), (0, 0).into()][..]));
+        assert_eq!(replay.frame(2), Some(&[(2, 0).into(), (1, 0).into()][..]));
+        assert_eq!(replay.frame(4), None);
+        assert_eq!(replay.command_of(0), None);
+        assert_eq!(replay.command_of(3), Some(1));
+        assert_eq!(replay.frame_of(1), 3);
+        assert_eq!(replay.visits_until(1, 1).len(), 1);
+        let bounds = replay.bounds().unwrap();
+        assert_eq!((bounds.min, bounds.max), ((0, 0).into(), (2, 1).into()));
+    }
+
+    #[test]
+    fn matches_the_rope() {
+        let input = include_str!("../examples/larger.txt");
+        let replay = Replay::from_input(10, input).unwrap();
+        let mut rope = Rope::new(10);
+        crate::simulate(&mut rope, input).unwrap();
+        let last = replay.frame_count() - 1;
+        assert_eq!(replay.frame(last), Some(rope.knots()));
+        assert_eq!(replay.visits_until(9, last).len(), 36);
+        assert_eq!(replay.visits_until(1, last), *rope.visits(1).unwrap());
+    }
+
+    #[test]
+    fn csv_and_json() {
+        let replay = short();
+        assert_eq!(
+            replay.to_csv(),
+            "frame,command,knot,x,y
+0,,0,0,0
+0,,1,0,0
+1,R 2,0,1,0
+1,R 2,1,0,0
+2,R 2,0,2,0
+2,R 2,1,1,0
+3,U 1,0,2,1
+3,U 1,1,1,0
+"
+        );
+        assert_eq!(
+            replay.to_json(),
+            r#"{"knots":2,"commands":["R 2","U 1"],"frames":[{"command":null,"knots":[[0,0],[0,0]]},{"command":0,"knots":[[1,0],[0,0]]},{"command":0,"knots":[[2,0],[1,0]]},{"command":1,"knots":[[2,1],[1,0]]}]}"#
+        );
+    }
+
+    #[test]
+    fn svg() {
+        let svg = short().to_svg(0.5);
+        assert!(svg.starts_with(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="16" viewBox="0 0 3 2">"#
+        ));
+        assert!(
+            svg.contains(r#"values="0.5;1.5;2.5;2.5" dur="2s""#),
+            "{svg}"
+        );
+        assert!(
+            svg.contains(r#"values="1.5;1.5;1.5;0.5" dur="2s""#),
+            "{svg}"
+        );
+        // the tail reaches its second cell on frame 2 of 4
+        assert!(svg.contains(r##"<rect x="1" y="1" width="1" height="1" fill="#2e7d32" opacity="0"><animate attributeName="opacity" values="0;1" keyTimes="0;0.5""##), "{svg}");
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
@@ -15,6 +15,18 @@ impl Position {
             y: self.y.abs(),
         }
     }
+
+    pub fn signum(self) -> Self {
+        Self {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+
+    /// Distance counting diagonal steps as one, the way knots touch
+    pub fn chebyshev(self) -> i32 {
+        self.x.abs().max(self.y.abs())
+    }
 }
 
 impl From<(i32, i32)> for Position {
@@ -61,10 +73,89 @@ impl std::ops::Sub for Position {
     }
 }
 
-#[derive(Debug)]
+/// Smallest rectangle holding a set of positions, both corners included
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Position,
+    pub max: Position,
+}
+
+impl Bounds {
+    fn at(position: Position) -> Self {
+        Self {
+            min: position,
+            max: position,
+        }
+    }
+
+    /// Bounds of all the `positions`, none if there are none
+    pub fn around(positions: impl IntoIterator<Item = Position>) -> Option<Self> {
+        let mut positions = positions.into_iter();
+        let mut bounds = Self::at(positions.next()?);
+        positions.for_each(|position| bounds.extend(position));
+        Some(bounds)
+    }
+
+    fn extend(&mut self, position: Position) {
+        self.min.x = self.min.x.min(position.x);
+        self.min.y = self.min.y.min(position.y);
+        self.max.x = self.max.x.max(position.x);
+        self.max.y = self.max.y.max(position.y);
+    }
+
+    pub fn width(&self) -> u32 {
+        self.min.x.abs_diff(self.max.x) + 1
+    }
+
+    pub fn height(&self) -> u32 {
+        self.min.y.abs_diff(self.max.y) + 1
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        (self.min.x..=self.max.x).contains(&position.x)
+            && (self.min.y..=self.max.y).contains(&position.y)
+    }
+
+    /// Smallest bounds holding both
+    pub fn union(self, other: Self) -> Self {
+        let mut union = self;
+        union.extend(other.min);
+        union.extend(other.max);
+        union
+    }
+}
+
+/// Where a knot has been
+#[derive(Debug, Clone)]
+struct Trail {
+    visits: HashSet<Position>,
+    bounds: Bounds,
+}
+
+impl Trail {
+    fn new(start: Position) -> Self {
+        Self {
+            visits: HashSet::from([start]),
+            bounds: Bounds::at(start),
+        }
+    }
+
+    fn visit(&mut self, position: Position) {
+        self.visits.insert(position);
+        self.bounds.extend(position);
+    }
+}
+
+/// Knots tied one after the other, the first one being the head.
+///
+/// A knot stays put while it is at most `slack` steps away from the one
+/// before it, diagonal steps counting as one. Further than that it moves one
+/// step toward it on each axis where they differ, until it is close enough.
+#[derive(Debug, Clone)]
 pub struct Rope {
     nodes: Vec<Position>,
-    tail_visits: HashSet<Position>,
+    slack: u32,
+    trails: Vec<Trail>,
 }
 
 impl Default for Rope {
@@ -74,87 +165,60 @@ impl Default for Rope {
 }
 
 impl Rope {
+    /// Rope of `nodes` knots, touching knots never move
     pub fn new(nodes: usize) -> Self {
-        if nodes == 0 {
-            return Self {
-                nodes: vec![],
-                tail_visits: HashSet::new(),
-            };
-        }
-
-        let mut tail_visits = HashSet::new();
-        tail_visits.insert(Position::default());
+        Self::with_slack(nodes, 1)
+    }
 
+    /// Rope of `nodes` knots, each one able to be `slack` steps away from the
+    /// previous one before it follows
+    pub fn with_slack(nodes: usize, slack: u32) -> Self {
         Self {
             nodes: vec![Position::default(); nodes],
-            tail_visits,
+            slack,
+            trails: vec![Trail::new(Position::default()); nodes],
         }
     }
 
+    pub fn slack(&self) -> u32 {
+        self.slack
+    }
+
+    pub fn knots(&self) -> &[Position] {
+        &self.nodes
+    }
+
     pub fn process_command(&mut self, cmd: Command) {
-        const STEP: i32 = 1;
-        // println!("{cmd:?}");
         for _ in 0..cmd.steps() {
-            // advance head
-            if let Some(head) = self.head_mut() {
-                // let steps = cmd.steps() as i32;
-                match cmd.direction() {
-                    Direction::Up => *head += (0, STEP).into(),
-                    Direction::Down => *head += (0, -STEP).into(),
-                    Direction::Left => *head += (-STEP, 0).into(),
-                    Direction::Right => *head += (STEP, 0).into(),
-                }
-            }
-            // println!("HEAD - {:?}", self.head().unwrap());
-            // println!("TAIL - {:?}", self.tail().unwrap());
-            // advance tail
-            // [1..] first is always a HEAD :)
-            for i in 1..self.nodes.len() {
-                //deltas between nearest nodes
-                let (dx, dy) = (self.nodes[i - 1] - self.nodes[i]).into();
-                // println!("{dx}-{dy}");
-                let to_add: Position = match (dx, dy) {
-                    // overlapping
-                    (0, 0) => (0, 0).into(),
-                    // one step nearby
-                    (1, 0) | (0, 1) | (0, -1) | (-1, 0) => (0, 0).into(),
-                    // diagonal nearby
-                    (1, 1) | (-1, 1) | (1, -1) | (-1, -1) => (0, 0).into(),
-                    // Up
-                    (0, 2) => (0, 1).into(),
-                    // Down
-                    (0, -2) => (0, -1).into(),
-                    // Left
-                    (-2, 0) => (-1, 0).into(),
-                    // Right
-                    (2, 0) => (1, 0).into(),
-                    // Up and Right
-                    (1, 2) | (2, 1) => (1, 1).into(),
-                    // Up and Left
-                    (-2, 1) | (-1, 2) => (-1, 1).into(),
-                    // Down and Right
-                    (2, -1) | (1, -2) => (1, -1).into(),
-                    // Down and Left
-                    (-2, -1) | (-1, -2) => (-1, -1).into(),
-                    // Diagonal
-                    (-2, -2) => (-1, -1).into(),
-                    (-2, 2) => (-1, 1).into(),
-                    (2, -2) => (1, -1).into(),
-                    (2, 2) => (1, 1).into(),
-                    _ => panic!("not expected"),
-                };
-                // println!("TO_ADD: {to_add:?}");
-                self.nodes[i] += to_add;
-
-                if i == self.nodes.len() - 1 {
-                    self.tail_visits.insert(self.nodes[i]);
-                }
-            }
+            self.step(cmd.direction());
         }
     }
 
-    pub fn tail_visits_count(&self) -> usize {
-        self.tail_visits.len()
+    /// Moves the head one step in `direction`, and the rest of the rope after
+    /// it
+    pub fn step(&mut self, direction: Direction) {
+        let Some(head) = self.head_mut() else {
+            return;
+        };
+        *head += direction.offset().into();
+        let head = *head;
+        self.trails[0].visit(head);
+        self.follow();
+    }
+
+    /// Moves every knot after the head toward the one before it
+    fn follow(&mut self) {
+        let slack = self.slack as i32;
+        for i in 1..self.nodes.len() {
+            loop {
+                let delta = self.nodes[i - 1] - self.nodes[i];
+                if delta.chebyshev() <= slack {
+                    break;
+                }
+                self.nodes[i] += delta.signum();
+                self.trails[i].visit(self.nodes[i]);
+            }
+        }
     }
 
     pub fn head(&self) -> Option<&Position> {
@@ -165,7 +229,95 @@ impl Rope {
         self.nodes.first_mut()
     }
 
-    pub fn tail_visits(&self) -> &HashSet<Position> {
-        &self.tail_visits
+    pub fn tail(&self) -> Option<&Position> {
+        self.nodes.last()
+    }
+
+    /// Positions knot `knot` visited at least once, the head being knot 0
+    pub fn visits(&self, knot: usize) -> Option<&HashSet<Position>> {
+        self.trails.get(knot).map(|trail| &trail.visits)
+    }
+
+    pub fn visit_count(&self, knot: usize) -> usize {
+        self.visits(knot).map_or(0, HashSet::len)
+    }
+
+    /// Area knot `knot` moved in
+    pub fn bounds(&self, knot: usize) -> Option<Bounds> {
+        self.trails.get(knot).map(|trail| trail.bounds)
+    }
+
+    /// Area the whole rope moved in, the head always being the furthest out
+    /// with the default slack
+    pub fn visited_bounds(&self) -> Option<Bounds> {
+        self.trails
+            .iter()
+            .map(|trail| trail.bounds)
+            .reduce(Bounds::union)
+    }
+
+    pub fn tail_visits(&self) -> Option<&HashSet<Position>> {
+        self.trails.last().map(|trail| &trail.visits)
+    }
+
+    pub fn tail_visits_count(&self) -> usize {
+        self.tail_visits().map_or(0, HashSet::len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(rope: &mut Rope, commands: &[(Direction, u32)]) {
+        for &(direction, steps) in commands {
+            rope.process_command(Command::new(direction, steps));
+        }
+    }
+
+    #[test]
+    fn knots_follow_diagonally() {
+        let mut rope = Rope::new(2);
+        run(&mut rope, &[(Direction::Up, 1), (Direction::Right, 1)]);
+        assert_eq!(rope.tail(), Some(&Position::default()));
+        rope.process_command(Command::new(Direction::Up, 1));
+        assert_eq!(rope.tail(), Some(&Position::from((1, 1))));
+
+        let mut rope = Rope::new(3);
+        run(&mut rope, &[(Direction::UpRight, 4)]);
+        assert_eq!(rope.knots(), [(4, 4).into(), (3, 3).into(), (2, 2).into()]);
+        assert_eq!(rope.visit_count(1), 4);
+        assert_eq!(rope.tail_visits_count(), 3);
+    }
+
+    #[test]
+    fn slack() {
+        let mut rope = Rope::with_slack(2, 3);
+        run(&mut rope, &[(Direction::Left, 3)]);
+        assert_eq!(rope.tail_visits_count(), 1);
+        run(&mut rope, &[(Direction::Left, 2), (Direction::Down, 4)]);
+        assert_eq!(rope.tail(), Some(&Position::from((-3, -1))));
+        assert_eq!(rope.visits(1).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn bounds() {
+        let mut rope = Rope::new(2);
+        run(
+            &mut rope,
+            &[
+                (Direction::Right, 4),
+                (Direction::Up, 3),
+                (Direction::DownLeft, 6),
+            ],
+        );
+        let head = rope.bounds(0).unwrap();
+        assert_eq!((head.min, head.max), ((-2, -3).into(), (4, 3).into()));
+        assert_eq!((head.width(), head.height()), (7, 7));
+        let tail = rope.bounds(1).unwrap();
+        assert!(head.contains(tail.min) && head.contains(tail.max));
+        assert_eq!(rope.visited_bounds(), Some(head));
+        assert_eq!(rope.bounds(2), None);
+        assert_eq!(Rope::new(0).visited_bounds(), None);
     }
 }
@@ -0,0 +1,158 @@
+//! Aho-Corasick automaton finding every word of a vocabulary in one pass,
+//! overlapping ones included
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::Range,
+};
+
+use crate::vocabulary::Vocabulary;
+
+const ROOT: usize = 0;
+
+/// A word found in the text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// Byte range of the word
+    pub span: Range<usize>,
+    pub value: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    next: BTreeMap<u8, usize>,
+    /// Longest proper suffix of this node which is also a node
+    fail: usize,
+    /// Words ending here, those of the suffixes included, as (length, value)
+    outputs: Vec<(usize, u32)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Automaton {
+    nodes: Vec<Node>,
+}
+
+impl Automaton {
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let mut nodes = vec![Node::default()];
+        for (word, value) in vocabulary.entries() {
+            let mut node = ROOT;
+            for &byte in word.as_bytes() {
+                node = match nodes[node].next.get(&byte) {
+                    Some(&next) => next,
+                    None => {
+                        nodes.push(Node::default());
+                        let next = nodes.len() - 1;
+                        nodes[node].next.insert(byte, next);
+                        next
+                    }
+                };
+            }
+            nodes[node].outputs.push((word.len(), *value));
+        }
+
+        // Breadth first, so the suffixes of a node are done before it
+        let mut automaton = Self { nodes };
+        let mut queue = automaton.nodes[ROOT]
+            .next
+            .values()
+            .copied()
+            .collect::<VecDeque<_>>();
+        while let Some(node) = queue.pop_front() {
+            let children = automaton.nodes[node].next.clone();
+            for (byte, child) in children {
+                let fail = match node {
+                    ROOT => ROOT,
+                    _ => automaton.step(automaton.nodes[node].fail, byte),
+                };
+                let inherited = automaton.nodes[fail].outputs.clone();
+                let child_node = &mut automaton.nodes[child];
+                child_node.fail = fail;
+                child_node.outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+        automaton
+    }
+
+    fn step(&self, mut node: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[node].next.get(&byte) {
+                return next;
+            }
+            if node == ROOT {
+                return ROOT;
+            }
+            node = self.nodes[node].fail;
+        }
+    }
+
+    /// Every word of `text`, in the order they end
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Match> + 'a {
+        text.bytes()
+            .enumerate()
+            .scan(ROOT, move |node, (idx, byte)| {
+                *node = self.step(*node, byte);
+                Some((idx + 1, &self.nodes[*node].outputs))
+            })
+            .flat_map(|(end, outputs)| {
+                outputs.iter().map(move |&(len, value)| Match {
+                    span: end - len..end,
+                    value,
+                })
+            })
+    }
+
+    /// Every word of `text`, sorted by where they start, the longest first
+    pub fn find_all(&self, text: &str) -> Vec<Match> {
+        let mut matches = self.find_iter(text).collect::<Vec<_>>();
+        matches.sort_by_key(|m| (m.span.start, std::cmp::Reverse(m.span.end)));
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(automaton: &Automaton, text: &str) -> Vec<(Range<usize>, u32)> {
+        automaton
+            .find_all(text)
+            .into_iter()
+            .map(|m| (m.span, m.value))
+            .collect()
+    }
+
+    #[test]
+    fn overlapping_words() {
+        let automaton = Automaton::new(&Vocabulary::digits().and(Vocabulary::english_up_to(9)));
+        assert_eq!(
+            values(&automaton, "eightwo3"),
+            [(0..5, 8), (4..7, 2), (7..8, 3)]
+        );
+        assert_eq!(values(&automaton, "oneight"), [(0..3, 1), (2..7, 8)]);
+        assert_eq!(values(&automaton, "xyz"), []);
+    }
+
+    #[test]
+    fn nested_words() {
+        let automaton = Automaton::new(&Vocabulary::english_up_to(20));
+        assert_eq!(values(&automaton, "seventeen"), [(0..9, 17), (0..5, 7)]);
+        assert_eq!(values(&automaton, "ninineteen"), [(2..10, 19), (2..6, 9)]);
+    }
+
+    #[test]
+    fn other_alphabets() {
+        let russian = Vocabulary::from_table("один 1\nдва 2\nдевять 9").unwrap();
+        let automaton = Automaton::new(&russian);
+        let text = "xдвадевять";
+        let matches = automaton.find_all(text);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| &text[m.span.clone()])
+                .collect::<Vec<_>>(),
+            ["два", "девять"]
+        );
+    }
+}
@@ -0,0 +1,77 @@
+pub mod automaton;
+pub mod vocabulary;
+
+use crate::{automaton::Automaton, vocabulary::Vocabulary};
+
+/// First digit times ten plus the last one, none if `line` has no digit.
+///
+/// The first digit is the word starting first and the last one the word
+/// ending last, the longest one on a tie, so `eightwo` is 82. A word standing
+/// for more than one digit counts as its decimal digits written out:
+/// `sixteen` is `16`, so `7sixteen` is 76 and `twenty` alone is 20.
+pub fn calibration_value(automaton: &Automaton, line: &str) -> Option<u32> {
+    let matches = automaton.find_all(line);
+    let first = matches.first()?;
+    let last = matches
+        .iter()
+        .max_by_key(|m| (m.span.end, std::cmp::Reverse(m.span.start)))?;
+    Some(leading_digit(first.value) * 10 + last.value % 10)
+}
+
+fn leading_digit(mut value: u32) -> u32 {
+    while value >= 10 {
+        value /= 10;
+    }
+    value
+}
+
+/// Sum of the calibration values of every line, lines without digits adding
+/// nothing
+pub fn calibration_sum(input: &str, vocabulary: &Vocabulary) -> u32 {
+    let automaton = Automaton::new(vocabulary);
+    input
+        .lines()
+        .filter_map(|line| calibration_value(&automaton, line))
+        .sum()
+}
+
+pub fn part_1(input: &str) -> u32 {
+    calibration_sum(input, &Vocabulary::digits())
+}
+
+pub fn part_2(input: &str) -> u32 {
+    calibration_sum(
+        input,
+        &Vocabulary::digits().and(Vocabulary::english_up_to(9)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    aoc_macros::examples! {
+        part1: part_1,
+        part2: part_2,
+    }
+
+    #[test]
+    fn calibration_values() {
+        let automaton = Automaton::new(&Vocabulary::digits().and(Vocabulary::english_up_to(20)));
+        assert_eq!(calibration_value(&automaton, "eightwo"), Some(82));
+        assert_eq!(calibration_value(&automaton, "7pqrstsixteen"), Some(76));
+        assert_eq!(calibration_value(&automaton, "xtwentyx"), Some(20));
+        assert_eq!(calibration_value(&automaton, "twelve3eleven"), Some(11));
+        assert_eq!(calibration_value(&automaton, "abc"), None);
+
+        let french = Vocabulary::from_table("un 1\ndeux 2\ntrois 3\nhuit 8").unwrap();
+        assert_eq!(calibration_sum("deuxtrois\nxhuitunx\n", &french), 23 + 81);
+    }
+
+    #[test]
+    fn answers() {
+        let input = include_str!("input.txt");
+        assert_eq!(part_1(input), 54605);
+        assert_eq!(part_2(input), 55429);
+    }
+}
@@ -0,0 +1,129 @@
+//! Words the scanner recognises as digits
+
+use aoc_common::prelude::{lines_with, ParseError, ParseErrorKind};
+
+const ENGLISH: [&str; 20] = [
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+    "twenty",
+];
+
+/// Words and the value each one stands for
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Vocabulary {
+    entries: Vec<(String, u32)>,
+}
+
+impl Vocabulary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `0` to `9` written as digits
+    pub fn digits() -> Self {
+        (0..=9).fold(Self::new(), |vocabulary, digit| {
+            vocabulary.with(&digit.to_string(), digit)
+        })
+    }
+
+    /// `one`, `two`, ... up to `limit` spelled in English, 20 at most
+    pub fn english_up_to(limit: u32) -> Self {
+        ENGLISH
+            .iter()
+            .zip(1..=limit)
+            .fold(Self::new(), |vocabulary, (word, value)| {
+                vocabulary.with(word, value)
+            })
+    }
+
+    /// Table with one `word value` pair per line, like
+    ///
+    /// ```text
+    /// un 1
+    /// deux 2
+    /// ```
+    pub fn from_table(table: &str) -> Result<Self, ParseError> {
+        let entries = lines_with(table, |line| {
+            let (word, value) = line.trim().split_once(char::is_whitespace).ok_or_else(|| {
+                ParseError::new(1, 1, ParseErrorKind::Expected("`word value`".to_owned()))
+            })?;
+            let value = value.trim();
+            let value = value.parse().map_err(|_| {
+                ParseError::at(line, value, ParseErrorKind::InvalidNumber(value.to_owned()))
+            })?;
+            Ok((word.to_owned(), value))
+        })?;
+        Ok(entries
+            .into_iter()
+            .fold(Self::new(), |vocabulary, (word, value)| {
+                vocabulary.with(&word, value)
+            }))
+    }
+
+    /// Adds `word` for `value`, ignored if empty
+    pub fn with(mut self, word: &str, value: u32) -> Self {
+        if !word.is_empty() {
+            self.entries.push((word.to_owned(), value));
+        }
+        self
+    }
+
+    /// Words of both vocabularies
+    pub fn and(mut self, other: Vocabulary) -> Self {
+        self.entries.extend(other.entries);
+        self
+    }
+
+    pub fn entries(&self) -> &[(String, u32)] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables() {
+        assert_eq!(Vocabulary::english_up_to(20).entries().len(), 20);
+        assert_eq!(
+            Vocabulary::english_up_to(30).entries().last(),
+            Some(&("twenty".to_owned(), 20))
+        );
+        assert_eq!(
+            Vocabulary::from_table("un 1\n  deux   2\n").unwrap(),
+            Vocabulary::new().with("un", 1).with("deux", 2)
+        );
+        let error = Vocabulary::from_table("un 1\ndeux two").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 6));
+        let error = Vocabulary::from_table("un 1\ndeux   two").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 8));
+        assert_eq!(error.kind, ParseErrorKind::InvalidNumber("two".to_owned()));
+        let error = Vocabulary::from_table("  один\tx").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 8));
+        let error = Vocabulary::from_table("un 1\ndeux").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert!(Vocabulary::new().with("", 0).is_empty());
+    }
+}
@@ -1,125 +1,242 @@
-#![allow(unused)]
+use std::collections::{BTreeMap, BTreeSet};
 
-use std::{
-    io::BufRead,
-    ops::{AddAssign, RemAssign},
-};
+use aoc_common::prelude::ParseError;
 
+mod parser;
+
+pub use parser::parse_games;
+
+/// Colours of the puzzle
+pub const COLOURS: [&str; 3] = ["red", "green", "blue"];
+
+/// Cubes of each colour, a colour left out counting as none
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Default, Clone)]
 pub struct Set {
-    pub red: u32,
-    pub green: u32,
-    pub blue: u32,
+    cubes: BTreeMap<String, u32>,
 }
 
 impl Set {
-    fn in_bounds(&self, other: &Set) -> bool {
-        self.red <= other.red && self.green <= other.green && self.blue <= other.blue
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, colour: &str, count: u32) -> Self {
+        self.cubes.insert(colour.to_owned(), count);
+        self
+    }
+
+    pub fn get(&self, colour: &str) -> u32 {
+        self.cubes.get(colour).copied().unwrap_or_default()
+    }
+
+    /// Colours and counts, sorted by colour
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.cubes
+            .iter()
+            .map(|(colour, &count)| (colour.as_str(), count))
     }
 
-    fn power(&self) -> u32 {
-        self.red * self.green * self.blue
+    pub fn total(&self) -> u32 {
+        self.cubes.values().sum()
     }
 
-    fn add_biggest_from(&mut self, mut rhs: Set) {
-        if self.red < rhs.red {
-            self.red = rhs.red
-        };
-        if self.green < rhs.green {
-            self.green = rhs.green
-        };
-        if self.blue < rhs.blue {
-            self.blue = rhs.blue
-        };
+    /// Whether `bag` has at least as many cubes of every colour
+    pub fn in_bounds(&self, bag: &Set) -> bool {
+        self.iter().all(|(colour, count)| count <= bag.get(colour))
+    }
+
+    /// Product of the counts of `colours`
+    pub fn power(&self, colours: &[&str]) -> u32 {
+        colours.iter().map(|colour| self.get(colour)).product()
+    }
+
+    /// Largest count of each colour of both sets
+    pub fn union(mut self, other: &Set) -> Self {
+        for (colour, count) in other.iter() {
+            let max = self.get(colour).max(count);
+            self.cubes.insert(colour.to_owned(), max);
+        }
+        self
     }
 }
 
-#[derive(Debug)]
-struct Game {
+impl<const N: usize> From<[(&str, u32); N]> for Set {
+    fn from(cubes: [(&str, u32); N]) -> Self {
+        cubes.into_iter().collect()
+    }
+}
+
+impl<'a> FromIterator<(&'a str, u32)> for Set {
+    fn from_iter<T: IntoIterator<Item = (&'a str, u32)>>(iter: T) -> Self {
+        iter.into_iter()
+            .fold(Set::new(), |set, (colour, count)| set.with(colour, count))
+    }
+}
+
+impl std::fmt::Display for Set {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, (colour, count)) in self.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{count} {colour}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Colour a game shows more cubes of than a bag holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortfall {
+    pub colour: String,
+    pub needed: u32,
+    pub available: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
     id: u32,
     sets: Vec<Set>,
     min_set: Set,
 }
 
 impl Game {
-    fn is_all_sets_in_bounds(&self, compare_set: &Set) -> bool {
-        self.sets.iter().all(|s| s.in_bounds(compare_set))
-    }
-}
-
-fn parse_game(input: &str) -> Game {
-    let idx = input.find(':').unwrap();
-    let id = input[..idx]
-        .split_ascii_whitespace()
-        .nth(1)
-        .unwrap()
-        .parse::<u32>()
-        .unwrap();
-    let mut min_set = Set::default();
-    let sets = input[idx + 1..]
-        .split_terminator(';')
-        .map(|set| {
-            let mut current_set = Set::default();
-            set.split_terminator(',').for_each(|cube| {
-                let mut it = cube.split_ascii_whitespace();
-                let count = it.next().unwrap();
-                let count = count.parse::<u32>().unwrap();
-                match it.next().unwrap() {
-                    "red" => current_set.red += count,
-                    "green" => current_set.green += count,
-                    "blue" => current_set.blue += count,
-                    _ => panic!("no such type of cube"),
-                }
-            });
-            min_set.add_biggest_from(current_set.clone());
-            current_set
-        })
-        .collect();
-
-    Game { id, sets, min_set }
-}
-
-struct PrettyGames<'a>(&'a Vec<Game>);
-
-impl std::fmt::Debug for PrettyGames<'_> {
+    pub fn new(id: u32, sets: Vec<Set>) -> Self {
+        let min_set = sets.iter().fold(Set::new(), |min, set| min.union(set));
+        Self { id, sets, min_set }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn sets(&self) -> &[Set] {
+        &self.sets
+    }
+
+    /// Fewest cubes of each colour the game could be played with
+    pub fn minimal_bag(&self) -> &Set {
+        &self.min_set
+    }
+
+    pub fn is_possible(&self, bag: &Set) -> bool {
+        self.min_set.in_bounds(bag)
+    }
+
+    /// Colours which make the game impossible with `bag`, none if it is
+    /// possible
+    pub fn limiting_colours(&self, bag: &Set) -> Vec<Shortfall> {
+        self.min_set
+            .iter()
+            .filter(|&(colour, needed)| needed > bag.get(colour))
+            .map(|(colour, needed)| Shortfall {
+                colour: colour.to_owned(),
+                needed,
+                available: bag.get(colour),
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Game {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.iter().for_each(|g| {
-            write!(f, "Game {}: ", g.id);
-            write!(f, "{:?}", g.sets);
-            writeln!(f);
-        });
+        write!(f, "Game {}: ", self.id)?;
+        for (idx, set) in self.sets.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{set}")?;
+        }
         Ok(())
     }
 }
 
-pub fn part1(input: &str, bag_limit: Set) -> u32 {
-    let games = input.lines().map(parse_game).collect::<Vec<_>>();
-    // dbg!(PrettyGames(&games));
+/// Ids of the games possible with `bag`
+pub fn possible_games<'a>(games: &'a [Game], bag: &'a Set) -> impl Iterator<Item = u32> + 'a {
+    games.iter().filter(|g| g.is_possible(bag)).map(Game::id)
+}
+
+/// Bag making the most games possible
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BestBag {
+    pub bag: Set,
+    /// Ids of the games possible with it
+    pub games: Vec<u32>,
+}
 
-    games
+/// Bag of at most `budget` cubes making the most games possible, the one
+/// with the fewest cubes on a tie.
+///
+/// Only the counts some game needs are worth trying for each colour, and the
+/// last colour can take whatever the others left.
+pub fn best_bag(games: &[Game], budget: u32) -> BestBag {
+    let colours = games
         .iter()
-        .filter_map(|g| match g.is_all_sets_in_bounds(&bag_limit) {
-            true => Some(g.id),
-            false => None,
-        })
-        .sum()
+        .flat_map(|g| g.minimal_bag().iter().map(|(colour, _)| colour))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let candidates = games.iter().collect::<Vec<_>>();
+    let mut best = BestBag {
+        bag: Set::new(),
+        games: possible_games(games, &Set::new()).collect(),
+    };
+    search(&colours, &candidates, budget, &mut best);
+    best
+}
+
+fn search(colours: &[&str], candidates: &[&Game], budget: u32, best: &mut BestBag) {
+    let Some((&colour, rest)) = colours.split_first() else {
+        let bag = candidates
+            .iter()
+            .fold(Set::new(), |bag, g| bag.union(g.minimal_bag()));
+        let better = candidates.len() > best.games.len()
+            || (candidates.len() == best.games.len() && bag.total() < best.bag.total());
+        if better {
+            best.bag = bag;
+            best.games = candidates.iter().map(|g| g.id()).collect();
+        }
+        return;
+    };
+    let counts = match rest.is_empty() {
+        true => BTreeSet::from([budget]),
+        false => candidates
+            .iter()
+            .map(|g| g.minimal_bag().get(colour))
+            .filter(|&count| count <= budget)
+            .chain([0])
+            .collect(),
+    };
+    for count in counts {
+        let left = candidates
+            .iter()
+            .copied()
+            .filter(|g| g.minimal_bag().get(colour) <= count)
+            .collect::<Vec<_>>();
+        // Fewer games can't beat the best bag
+        if left.len() >= best.games.len() {
+            search(rest, &left, budget - count, best);
+        }
+    }
+}
+
+pub fn part1(input: &str, bag_limit: Set) -> Result<u32, ParseError> {
+    let games = parse_games(input)?;
+    Ok(possible_games(&games, &bag_limit).sum())
 }
 
-pub fn part2(input: &str) -> u32 {
-    let games = input.lines().map(parse_game).collect::<Vec<_>>();
-    // dbg!(PrettyGames(&games));
-    games.iter().map(|g| g.min_set.power()).sum()
+pub fn part2(input: &str) -> Result<u32, ParseError> {
+    let games = parse_games(input)?;
+    Ok(games.iter().map(|g| g.minimal_bag().power(&COLOURS)).sum())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const BAG_LIMIT: Set = Set {
-        red: 12,
-        green: 13,
-        blue: 14,
-    };
+    fn bag_limit() -> Set {
+        Set::from([("red", 12), ("green", 13), ("blue", 14)])
+    }
 
     const PART1_SMALL: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
@@ -129,36 +246,96 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(PART1_SMALL, BAG_LIMIT), 8);
+        assert_eq!(part1(PART1_SMALL, bag_limit()), Ok(8));
     }
 
     #[test]
     fn test_game1() {
-        assert_eq!(part2(PART1_SMALL.lines().next().unwrap()), 48);
+        assert_eq!(part2(PART1_SMALL.lines().next().unwrap()), Ok(48));
     }
 
     #[test]
     fn test_game2() {
-        assert_eq!(part2(PART1_SMALL.lines().nth(1).unwrap()), 12);
+        assert_eq!(part2(PART1_SMALL.lines().nth(1).unwrap()), Ok(12));
     }
 
     #[test]
     fn test_game3() {
-        assert_eq!(part2(PART1_SMALL.lines().nth(2).unwrap()), 1560);
+        assert_eq!(part2(PART1_SMALL.lines().nth(2).unwrap()), Ok(1560));
     }
 
     #[test]
     fn test_game4() {
-        assert_eq!(part2(PART1_SMALL.lines().nth(3).unwrap()), 630);
+        assert_eq!(part2(PART1_SMALL.lines().nth(3).unwrap()), Ok(630));
     }
 
     #[test]
     fn test_game5() {
-        assert_eq!(part2(PART1_SMALL.lines().nth(4).unwrap()), 36);
+        assert_eq!(part2(PART1_SMALL.lines().nth(4).unwrap()), Ok(36));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(PART1_SMALL), 2286);
+        assert_eq!(part2(PART1_SMALL), Ok(2286));
+    }
+
+    #[test]
+    fn answers() {
+        let input = include_str!("input.txt");
+        assert_eq!(part1(input, bag_limit()), Ok(2913));
+        assert_eq!(part2(input), Ok(55593));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let games = parse_games(PART1_SMALL).unwrap();
+        // Colours come out sorted
+        assert_eq!(
+            games[0].to_string(),
+            "Game 1: 3 blue, 4 red; 6 blue, 2 green, 1 red; 2 green"
+        );
+        assert_eq!(parse_games(&games[0].to_string()).unwrap()[0], games[0]);
+    }
+
+    #[test]
+    fn limiting_colours() {
+        let games = parse_games(PART1_SMALL).unwrap();
+        assert_eq!(
+            games[2].minimal_bag(),
+            &Set::from([("red", 20), ("green", 13), ("blue", 6)])
+        );
+        assert_eq!(
+            games[2].limiting_colours(&bag_limit()),
+            [Shortfall {
+                colour: "red".to_owned(),
+                needed: 20,
+                available: 12
+            }]
+        );
+        assert!(games[0].limiting_colours(&bag_limit()).is_empty());
+        let no_blue = Set::from([("red", 100), ("green", 100)]);
+        assert_eq!(possible_games(&games, &no_blue).count(), 0);
+    }
+
+    #[test]
+    fn best_bags() {
+        let games = parse_games(PART1_SMALL).unwrap();
+        // Minimal bags (blue, green, red): 1 6 2 4, 2 4 3 1, 3 6 13 20,
+        // 4 15 3 14, 5 2 3 6
+        let best = best_bag(&games, 100);
+        assert_eq!(best.games, [1, 2, 3, 4, 5]);
+        assert_eq!(
+            best.bag,
+            Set::from([("red", 20), ("green", 13), ("blue", 15)])
+        );
+
+        let best = best_bag(&games, 20);
+        assert_eq!(best.games, [1, 2, 5]);
+        assert_eq!(best.bag, Set::from([("red", 6), ("green", 3), ("blue", 6)]));
+        assert!(best.bag.total() <= 20);
+
+        let best = best_bag(&games, 0);
+        assert!(best.games.is_empty());
+        assert_eq!(best.bag, Set::new());
     }
 }
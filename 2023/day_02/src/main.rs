@@ -1,13 +1,26 @@
-use day_02::{part1, part2, Set};
+use day_02::{best_bag, parse_games, part1, part2, Set};
+
+/// Total cubes of the puzzle's bag
+const BUDGET: u32 = 39;
 
 fn main() {
-    const BAG: Set = Set {
-        red: 12,
-        green: 13,
-        blue: 14,
-    };
+    let bag = Set::from([("red", 12), ("green", 13), ("blue", 14)]);
 
-    let input = include_str!("input.txt");
-    println!("Part 1 answer: {}", part1(input, BAG));
-    println!("Part 2 answer: {}", part2(input));
+    let input = &aoc_common::puzzle_input!("input.txt");
+    match (part1(input, bag), part2(input), parse_games(input)) {
+        (Ok(part1), Ok(part2), Ok(games)) => {
+            println!("Part 1 answer: {part1}");
+            println!("Part 2 answer: {part2}");
+            let best = best_bag(&games, BUDGET);
+            println!(
+                "Best bag of {BUDGET} cubes: {} ({} games possible)",
+                best.bag,
+                best.games.len()
+            );
+        }
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,84 @@
+use aoc_common::prelude::{combinator, lines_with, run, ParseError};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, space0, space1},
+    combinator::cut,
+    multi::separated_list1,
+    sequence::{pair, preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+
+use crate::{Game, Set};
+
+/// `3 blue, 4 red`, a colour shown twice counting twice.
+///
+/// Every pair is cut, so a bad one fails where it is instead of ending the
+/// list early.
+fn set(input: &str) -> IResult<&str, Set> {
+    let cubes = separated_pair(combinator::unsigned::<u32>(), space1, alpha1);
+    let (rest, cubes) = separated_list1(tuple((space0, tag(","), space0)), cut(cubes))(input)?;
+    let set = cubes.into_iter().fold(Set::new(), |set, (count, colour)| {
+        let total = set.get(colour) + count;
+        set.with(colour, total)
+    });
+    Ok((rest, set))
+}
+
+/// `Game 1: 3 blue, 4 red; 1 red, 2 green`, everything after `Game ` being
+/// cut
+pub fn game(input: &str) -> IResult<&str, Game> {
+    let id = terminated(combinator::unsigned::<u32>(), tuple((tag(":"), space0)));
+    let sets = separated_list1(tuple((space0, tag(";"), space0)), set);
+    let (rest, (id, sets)) = preceded(tuple((tag("Game"), space1)), cut(pair(id, sets)))(input)?;
+    Ok((rest, Game::new(id, sets)))
+}
+
+/// One game per line, errors pointing at the token that broke the line
+pub fn parse_games(input: &str) -> Result<Vec<Game>, ParseError> {
+    lines_with(input, |line| run(game, line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_common::prelude::ParseErrorKind;
+
+    #[test]
+    fn games() {
+        let games = parse_games("Game 1: 3 blue, 4 red; 1 red, 2 red\nGame 2: 1 teal\n").unwrap();
+        assert_eq!(games[0].id(), 1);
+        assert_eq!(
+            games[0].sets(),
+            [
+                Set::from([("blue", 3), ("red", 4)]),
+                Set::from([("red", 3)])
+            ]
+        );
+        assert_eq!(games[1].sets(), [Set::from([("teal", 1)])]);
+    }
+
+    #[test]
+    fn located_errors() {
+        let expected = |what: &str| ParseErrorKind::Expected(what.to_owned());
+
+        let error = parse_games("Game 1: 3 blue\nGame 2: 3 blue, red").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 17));
+        assert_eq!(error.kind, expected("digit"));
+
+        let error = parse_games("Game 1: 3 blue\nGame x: 3 blue").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 6));
+        assert_eq!(error.kind, expected("digit"));
+
+        let error = parse_games("Game 1: 3 blue; 4 5").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 19));
+        assert_eq!(error.kind, expected("alphabetic"));
+
+        let error = parse_games("Game 1: 3 blue;").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 16));
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedEnd);
+
+        let error = parse_games("Game 1: 3 blue\nRound 2: 3 blue").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+        assert_eq!(error.kind, expected("tag"));
+    }
+}
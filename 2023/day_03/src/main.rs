@@ -1,7 +1,15 @@
 use day_03::{part1, part2};
 
 fn main() {
-    let input = include_str!("input.txt");
-    println!("Part 1 answer: {}", part1(input));
-    println!("Part 2 answer: {}", part2(input));
+    let input = &aoc_common::puzzle_input!("input.txt");
+    match (part1(input), part2(input)) {
+        (Ok(part1), Ok(part2)) => {
+            println!("Part 1 answer: {part1}");
+            println!("Part 2 answer: {part2}");
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
 }
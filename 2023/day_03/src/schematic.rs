@@ -0,0 +1,215 @@
+//! Engine schematic read in one pass, with which numbers touch which symbols
+
+use std::{collections::HashMap, ops::Range};
+
+use aoc_common::prelude::{ParseError, ParseErrorKind};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Position {
+    pub row: usize,
+    pub col: usize,
+}
+
+/// Run of digits on a row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub value: u32,
+    pub row: usize,
+    pub cols: Range<usize>,
+}
+
+impl Number {
+    /// Cells around the number, diagonals included
+    fn neighbours(&self) -> impl Iterator<Item = Position> + '_ {
+        let rows = self.row.saturating_sub(1)..=self.row + 1;
+        let cols = self.cols.start.saturating_sub(1)..=self.cols.end;
+        rows.flat_map(move |row| cols.clone().map(move |col| Position { row, col }))
+            .filter(|p| p.row != self.row || !self.cols.contains(&p.col))
+    }
+}
+
+/// Anything but a digit or a `.`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub glyph: char,
+    pub position: Position,
+}
+
+pub type NumberId = usize;
+pub type SymbolId = usize;
+
+/// Numbers, symbols and the graph of which touch each other
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    /// Symbols touching each number
+    number_edges: Vec<Vec<SymbolId>>,
+    /// Numbers touching each symbol
+    symbol_edges: Vec<Vec<NumberId>>,
+}
+
+impl Schematic {
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Symbols touching number `id`
+    pub fn symbols_of(&self, id: NumberId) -> impl Iterator<Item = &Symbol> + '_ {
+        self.number_edges[id].iter().map(|&s| &self.symbols[s])
+    }
+
+    /// Numbers touching symbol `id`
+    pub fn numbers_of(&self, id: SymbolId) -> impl Iterator<Item = &Number> + '_ {
+        self.symbol_edges[id].iter().map(|&n| &self.numbers[n])
+    }
+
+    /// Numbers touching at least one symbol
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> + '_ {
+        self.numbers
+            .iter()
+            .zip(&self.number_edges)
+            .filter(|(_, symbols)| !symbols.is_empty())
+            .map(|(number, _)| number)
+    }
+
+    /// Numbers touching at least one `glyph`
+    pub fn numbers_touching(&self, glyph: char) -> impl Iterator<Item = &Number> + '_ {
+        self.numbers
+            .iter()
+            .zip(&self.number_edges)
+            .filter(move |(_, symbols)| symbols.iter().any(|&s| self.symbols[s].glyph == glyph))
+            .map(|(number, _)| number)
+    }
+
+    /// Every `glyph` touching exactly `parts` numbers, with those numbers
+    pub fn gears(
+        &self,
+        glyph: char,
+        parts: usize,
+    ) -> impl Iterator<Item = (&Symbol, Vec<&Number>)> + '_ {
+        self.symbols
+            .iter()
+            .zip(&self.symbol_edges)
+            .filter(move |(symbol, numbers)| symbol.glyph == glyph && numbers.len() == parts)
+            .map(|(symbol, numbers)| (symbol, numbers.iter().map(|&n| &self.numbers[n]).collect()))
+    }
+}
+
+impl std::str::FromStr for Schematic {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut schematic = Schematic::default();
+        let mut symbol_at = HashMap::new();
+        for (row, line) in s.lines().enumerate() {
+            let mut digits: Option<(usize, u32)> = None;
+            // A `.` past the end of the line closes the last number
+            for (col, c) in line.chars().chain(['.']).enumerate() {
+                if let Some(digit) = c.to_digit(10) {
+                    let (start, value) = digits.unwrap_or((col, 0));
+                    let value = value.checked_mul(10).and_then(|v| v.checked_add(digit));
+                    let value = value.ok_or_else(|| {
+                        let number = line.chars().skip(start).take_while(char::is_ascii_digit);
+                        let kind = ParseErrorKind::InvalidNumber(number.collect());
+                        ParseError::new(row + 1, start + 1, kind)
+                    })?;
+                    digits = Some((start, value));
+                    continue;
+                }
+                if let Some((start, value)) = digits.take() {
+                    schematic.numbers.push(Number {
+                        value,
+                        row,
+                        cols: start..col,
+                    });
+                }
+                if c != '.' && !c.is_whitespace() {
+                    let position = Position { row, col };
+                    symbol_at.insert(position, schematic.symbols.len());
+                    schematic.symbols.push(Symbol { glyph: c, position });
+                }
+            }
+        }
+
+        schematic.symbol_edges = vec![Vec::new(); schematic.symbols.len()];
+        for (id, number) in schematic.numbers.iter().enumerate() {
+            let symbols = number
+                .neighbours()
+                .filter_map(|p| symbol_at.get(&p).copied())
+                .collect::<Vec<_>>();
+            for &symbol in &symbols {
+                schematic.symbol_edges[symbol].push(id);
+            }
+            schematic.number_edges.push(symbols);
+        }
+        Ok(schematic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scanning() {
+        let schematic = "12.$\n.*.7\n99".parse::<Schematic>().unwrap();
+        assert_eq!(
+            schematic.numbers(),
+            [
+                Number {
+                    value: 12,
+                    row: 0,
+                    cols: 0..2
+                },
+                Number {
+                    value: 7,
+                    row: 1,
+                    cols: 3..4
+                },
+                Number {
+                    value: 99,
+                    row: 2,
+                    cols: 0..2
+                },
+            ]
+        );
+        assert_eq!(
+            schematic
+                .symbols()
+                .iter()
+                .map(|s| s.glyph)
+                .collect::<String>(),
+            "$*"
+        );
+        let star = schematic.numbers_of(1).map(|n| n.value).collect::<Vec<_>>();
+        assert_eq!(star, [12, 99]);
+        assert_eq!(
+            schematic.symbols_of(1).map(|s| s.glyph).collect::<String>(),
+            "$"
+        );
+        assert_eq!(schematic.gears('*', 2).count(), 1);
+        assert_eq!(schematic.gears('*', 3).count(), 0);
+        assert_eq!(
+            schematic
+                .numbers_touching('$')
+                .map(|n| n.value)
+                .collect::<Vec<_>>(),
+            [7]
+        );
+    }
+
+    #[test]
+    fn numbers_too_large() {
+        let error = "1.99999999999\n".parse::<Schematic>().unwrap_err();
+        assert_eq!((error.line, error.column), (1, 3));
+        assert_eq!(
+            error.kind,
+            ParseErrorKind::InvalidNumber("99999999999".to_owned())
+        );
+    }
+}
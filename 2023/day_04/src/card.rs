@@ -1,19 +1,8 @@
-const PIPE: char = '|';
-
-#[derive(thiserror::Error, Debug)]
-pub enum CardParseError {
-    #[error("Should be only one pipe symbol")]
-    Pipe(String),
-
-    #[error("Card must start from the Card N: syntax, found {0}")]
-    DoubleColon(String),
+use aoc_common::prelude::{lines_with, split_pair, ParseError, ParseErrorKind};
 
-    // #[error(transparent)]
-    #[error("Card number could not be parsed {0}")]
-    ParseInt(#[source] std::num::ParseIntError, String),
-}
+const PIPE: char = '|';
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
 pub struct Card {
     id: u32,
     win: Vec<u32>,
@@ -21,67 +10,62 @@ pub struct Card {
 }
 
 impl Card {
-    pub fn points(&self) -> u32 {
-        self.win
-            .iter()
-            .filter(|w| self.hand.as_slice().binary_search(w).is_ok())
-            .enumerate()
-            .fold(0u32, |mut acc, (i, _)| {
-                match i {
-                    0 => acc += 1,
-                    _ => acc *= 2,
-                };
-                acc
-            })
+    pub fn new(id: u32, mut win: Vec<u32>, mut hand: Vec<u32>) -> Self {
+        win.sort_unstable();
+        hand.sort_unstable();
+        Self { id, win, hand }
     }
 
-    pub fn matches_count(&self) -> usize {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Winning numbers found in the hand, in increasing order
+    pub fn matching_numbers(&self) -> impl Iterator<Item = u32> + '_ {
         self.win
             .iter()
-            .filter(|w| self.hand.as_slice().binary_search(w).is_ok())
-            .count()
+            .copied()
+            .filter(|w| self.hand.binary_search(w).is_ok())
     }
 
-    fn parse_one(card_line: &str) -> Result<Card, CardParseError> {
-        let (id, numbers) = card_line
-            .split_once(':')
-            .ok_or(CardParseError::DoubleColon(card_line.to_owned()))?;
-
-        let id = id
-            .trim_start_matches("Card") // Remove Card word
-            .trim_start() // Remove whitespaces before ID
-            .parse::<u32>()
-            .map_err(|e| CardParseError::ParseInt(e, id.to_owned()))?;
-
-        let (win, hand) = numbers
-            .split_once(PIPE)
-            .ok_or(CardParseError::Pipe(numbers.to_owned()))?;
-
-        let mut win = Card::to_vec(win)?;
-        let mut hand = Card::to_vec(hand)?;
-        win.sort();
-        hand.sort();
+    pub fn matches_count(&self) -> usize {
+        self.matching_numbers().count()
+    }
 
-        Ok(Card { id, win, hand })
+    /// `Card N: 1 2 3 | 4 5 6`
+    fn parse_one(card_line: &str) -> Result<Card, ParseError> {
+        let (label, numbers) = split_pair(card_line, ':')?;
+        let id = label
+            .strip_prefix("Card")
+            .filter(|rest| rest.starts_with(char::is_whitespace))
+            .ok_or_else(|| ParseError::new(1, 1, ParseErrorKind::Expected("`Card N:`".to_owned())))?
+            .trim_start();
+        let id = Card::number(card_line, id)?;
+
+        let (win, hand) = split_pair(numbers, PIPE).map_err(|e| e.within(card_line, numbers))?;
+        let win = Card::to_vec(card_line, win)?;
+        let hand = Card::to_vec(card_line, hand)?;
+        Ok(Card::new(id, win, hand))
     }
 
-    fn to_vec(input: &str) -> Result<Vec<u32>, CardParseError> {
+    fn to_vec(card_line: &str, input: &str) -> Result<Vec<u32>, ParseError> {
         input
             .split_ascii_whitespace()
-            .map(|n| {
-                n.parse::<u32>()
-                    .map_err(|e| CardParseError::ParseInt(e, n.to_owned()))
-            })
+            .map(|n| Card::number(card_line, n))
             .collect()
     }
 
-    pub fn id(&self) -> u32 {
-        self.id
+    /// `number`, a slice of `card_line`, errors pointing at it
+    fn number(card_line: &str, number: &str) -> Result<u32, ParseError> {
+        number.parse().map_err(|_| {
+            let kind = ParseErrorKind::InvalidNumber(number.to_owned());
+            ParseError::at(card_line, number, kind)
+        })
     }
 }
 
 impl std::str::FromStr for Card {
-    type Err = CardParseError;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Card::parse_one(s)
@@ -94,8 +78,36 @@ impl std::fmt::Debug for Card {
     }
 }
 
-pub fn parse_cards(input: &str) -> Result<Vec<Card>, CardParseError> {
-    use std::str::FromStr;
+/// One card per line
+pub fn parse_cards(input: &str) -> Result<Vec<Card>, ParseError> {
+    lines_with(input, Card::parse_one)
+}
 
-    input.lines().map(Card::from_str).collect()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn located_errors() {
+        let error = parse_cards("Card 1: 1 | 1\nCard 2: 3 4 5").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 14));
+        assert_eq!(error.kind, ParseErrorKind::Expected("`|`".to_owned()));
+        let error = parse_cards("Card 1: 1 | 1\nCard 2: 3 x | 5").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 11));
+        assert_eq!(error.kind, ParseErrorKind::InvalidNumber("x".to_owned()));
+        let error = parse_cards("Card1: 1 | 1").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+
+    #[test]
+    fn matching_numbers() {
+        let card = "Card   7: 41 48 83 86 17 | 83 86  6 31 17  9 48 53"
+            .parse::<Card>()
+            .unwrap();
+        assert_eq!(card.id(), 7);
+        assert_eq!(
+            card.matching_numbers().collect::<Vec<_>>(),
+            [17, 48, 83, 86]
+        );
+    }
 }
@@ -0,0 +1,92 @@
+//! Copies won by cards winning copies of the cards below them
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    ops::Range,
+};
+
+use crate::card::Card;
+
+/// Copies of every card once the winning is over, worked out in a single
+/// pass over the table.
+///
+/// A card with `n` matches wins one copy of each of the `n` cards below it
+/// per copy of itself, cards past the end of the table not being won.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cascade {
+    ids: Vec<u32>,
+    copies: Vec<u64>,
+    /// Positions of the cards each card wins
+    wins: Vec<Range<usize>>,
+    positions: HashMap<u32, usize>,
+}
+
+impl Cascade {
+    pub fn new(cards: &[Card]) -> Self {
+        let count = cards.len();
+        let wins = cards
+            .iter()
+            .enumerate()
+            .map(|(pos, card)| pos + 1..(pos + 1 + card.matches_count()).min(count))
+            .collect::<Vec<_>>();
+
+        // Won ranges all start right below their card, so a running total of
+        // the copies being handed out, less those whose range ended, is what
+        // each card gets
+        let mut expiring = vec![0u64; count + 1];
+        let mut handed_out = 0;
+        let mut copies = Vec::with_capacity(count);
+        for (pos, won) in wins.iter().enumerate() {
+            handed_out -= expiring[pos];
+            let own = 1 + handed_out;
+            copies.push(own);
+            if !won.is_empty() {
+                handed_out += own;
+                expiring[won.end] += own;
+            }
+        }
+
+        Self {
+            ids: cards.iter().map(Card::id).collect(),
+            copies,
+            wins,
+            positions: cards
+                .iter()
+                .enumerate()
+                .map(|(pos, c)| (c.id(), pos))
+                .collect(),
+        }
+    }
+
+    /// Card ids and how many copies of each there are, the original included,
+    /// in table order
+    pub fn copies(&self) -> impl Iterator<Item = (u32, u64)> + '_ {
+        self.ids.iter().copied().zip(self.copies.iter().copied())
+    }
+
+    /// Copies of card `id`, none if there is no such card
+    pub fn copies_of(&self, id: u32) -> Option<u64> {
+        self.positions.get(&id).map(|&pos| self.copies[pos])
+    }
+
+    /// Ids of the cards one copy of card `id` wins
+    pub fn won_by(&self, id: u32) -> &[u32] {
+        match self.positions.get(&id) {
+            Some(&pos) => &self.ids[self.wins[pos].clone()],
+            None => &[],
+        }
+    }
+
+    /// Ids of the cards won at least once
+    pub fn won_ids(&self) -> BTreeSet<u32> {
+        self.copies()
+            .filter(|&(_, copies)| copies > 1)
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    /// Cards in hand at the end, originals included
+    pub fn total(&self) -> u64 {
+        self.copies.iter().sum()
+    }
+}
@@ -0,0 +1,101 @@
+mod card;
+pub mod cascade;
+pub mod scoring;
+
+use aoc_common::prelude::ParseError;
+
+pub use card::{parse_cards, Card};
+pub use cascade::Cascade;
+pub use scoring::Scoring;
+
+/// Points of every card with `scoring`
+pub fn points(cards: &[Card], scoring: &Scoring) -> u32 {
+    cards
+        .iter()
+        .map(|c| scoring.points(c.matches_count()))
+        .sum()
+}
+
+pub fn part1(input: &str) -> Result<u32, ParseError> {
+    let cards = parse_cards(input)?;
+    Ok(points(&cards, &Scoring::PUZZLE))
+}
+
+pub fn part2(input: &str) -> Result<u64, ParseError> {
+    let cards = parse_cards(input)?;
+    Ok(Cascade::new(&cards).total())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(TEST), Ok(13));
+    }
+
+    #[test]
+    fn test_multiple_spaces() {
+        const MULT_SPACE: &str = "Card    1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
+        assert_eq!(part1(MULT_SPACE), Ok(8));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(TEST), Ok(30));
+    }
+
+    #[test]
+    fn answers() {
+        let input = include_str!("input.txt");
+        assert_eq!(part1(input), Ok(32001));
+        assert_eq!(part2(input), Ok(5037841));
+    }
+
+    #[test]
+    fn cascade() {
+        let cascade = Cascade::new(&parse_cards(TEST).unwrap());
+        assert_eq!(
+            cascade.copies().collect::<Vec<_>>(),
+            [(1, 1), (2, 2), (3, 4), (4, 8), (5, 14), (6, 1)]
+        );
+        assert_eq!(cascade.copies_of(4), Some(8));
+        assert_eq!(cascade.copies_of(7), None);
+        assert_eq!(cascade.won_by(1), [2, 3, 4, 5]);
+        assert_eq!(cascade.won_by(4), [5]);
+        assert_eq!(cascade.won_by(6), []);
+        assert_eq!(
+            cascade.won_ids().into_iter().collect::<Vec<_>>(),
+            [2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn wins_past_the_table() {
+        // Card 2 could win two cards, but only one is left below it
+        let cards = parse_cards("Card 1: 1 2 | 1 2\nCard 2: 3 4 | 3 4\nCard 3: 5 | 6").unwrap();
+        let cascade = Cascade::new(&cards);
+        assert_eq!(cascade.won_by(2), [3]);
+        assert_eq!(
+            cascade.copies().map(|(_, c)| c).collect::<Vec<_>>(),
+            [1, 2, 4]
+        );
+        assert_eq!(cascade.total(), 7);
+    }
+
+    #[test]
+    fn other_scorings() {
+        let cards = parse_cards(TEST).unwrap();
+        // 4, 2, 2, 1, 0, 0 matches
+        assert_eq!(points(&cards, &Scoring::PerMatch(1)), 9);
+        assert_eq!(points(&cards, &Scoring::Table(vec![5, 7])), 26);
+    }
+}
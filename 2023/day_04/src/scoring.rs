@@ -0,0 +1,64 @@
+//! How many points a card is worth for its matches
+
+/// Points for a number of matches, none being worth nothing with every rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scoring {
+    /// One point for the first match, doubled for each further one
+    Doubling,
+    /// The same points for every match
+    PerMatch(u32),
+    /// Points for 1, 2, ... matches, more matches than the table has scoring
+    /// like its last entry
+    Table(Vec<u32>),
+}
+
+impl Scoring {
+    /// The puzzle's rule
+    pub const PUZZLE: Scoring = Scoring::Doubling;
+
+    /// Points for `matches`, capped at `u32::MAX`
+    pub fn points(&self, matches: usize) -> u32 {
+        if matches == 0 {
+            return 0;
+        }
+        match self {
+            Scoring::Doubling => {
+                let exponent = u32::try_from(matches - 1).unwrap_or(u32::MAX);
+                2u32.saturating_pow(exponent)
+            }
+            Scoring::PerMatch(points) => {
+                let matches = u32::try_from(matches).unwrap_or(u32::MAX);
+                points.saturating_mul(matches)
+            }
+            Scoring::Table(table) => table
+                .get(matches - 1)
+                .or(table.last())
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl Default for Scoring {
+    fn default() -> Self {
+        Self::PUZZLE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules() {
+        let points = |scoring: Scoring| (0..6).map(|m| scoring.points(m)).collect::<Vec<_>>();
+        assert_eq!(points(Scoring::Doubling), [0, 1, 2, 4, 8, 16]);
+        assert_eq!(points(Scoring::PerMatch(3)), [0, 3, 6, 9, 12, 15]);
+        assert_eq!(
+            points(Scoring::Table(vec![1, 10, 100])),
+            [0, 1, 10, 100, 100, 100]
+        );
+        assert_eq!(points(Scoring::Table(Vec::new())), [0; 6]);
+        assert_eq!(Scoring::Doubling.points(40), u32::MAX);
+    }
+}
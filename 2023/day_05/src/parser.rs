@@ -1,6 +1,6 @@
 use nom::{
     bytes::complete::tag,
-    character::complete::{alpha1, line_ending, space1},
+    character::complete::{alpha1, newline, space1},
     combinator::{all_consuming, map, opt},
     multi::{many1, separated_list1},
     sequence::{preceded, separated_pair, terminated, tuple},
@@ -31,26 +31,25 @@ fn parse_seeds(input: &str) -> IResult<&str, Vec<Seed>> {
             tag("seeds: "),
             separated_list1(space1, nom::character::complete::u64),
         ),
-        line_ending,
+        newline,
     )(input)
 }
 
 fn parse_source_to_dest(input: &str) -> IResult<&str, Map> {
     use nom::character::complete::u64 as nom_u64;
-    // \r\n or \n
     // seed-to-soil map:
     let (input, (source_name, destination_name)) = preceded(
-        line_ending,
+        newline,
         separated_pair(
             alpha1,
             tag("-to-"),
-            terminated(terminated(alpha1, tag(" map:")), line_ending),
+            terminated(terminated(alpha1, tag(" map:")), newline),
         ),
     )(input)?;
 
-    // 50 98 2\r\n
+    // 50 98 2
     let (input, mut ranges) = many1(map(
-        tuple((nom_u64, space1, nom_u64, space1, nom_u64, opt(line_ending))),
+        tuple((nom_u64, space1, nom_u64, space1, nom_u64, opt(newline))),
         |(destination_start, _, source_start, _, length, _)| SeedRange {
             source_range: source_start..=source_start + length - 1,
             dest_range: destination_start..=destination_start + length - 1,
@@ -75,8 +74,7 @@ mod tests {
     use rstest::rstest;
 
     #[rstest]
-    #[case::carrige_return("seeds: 3 1\r\n",vec![3,1])]
-    #[case::no_carrige_return("seeds: 2 1\n",vec![2,1])]
+    #[case::one_line("seeds: 2 1\n",vec![2,1])]
     fn test_parse_seeds(#[case] input: &str, #[case] expected: Vec<Seed>) {
         let result = parse_seeds(input);
         assert_eq!(result, Ok(("", expected)));
@@ -1,3 +1,5 @@
+use aoc_common::prelude::combinator::{labelled, numbers, run};
+
 #[derive(Debug, PartialEq, Eq)]
 struct Race {
     time: usize,
@@ -31,22 +33,16 @@ impl From<(usize, usize)> for Race {
 
 fn parse_races(input: &str) -> Vec<Race> {
     let mut lines_it = input.lines().take(2);
-    let times = lines_it
-        .next()
-        .expect("First line not found")
-        .trim_start_matches("Time: ")
-        .split_ascii_whitespace()
-        .map(str::parse::<usize>)
-        .collect::<Result<Vec<_>, _>>()
-        .expect("expected successful parsing of time");
-    let distances = lines_it
-        .next()
-        .expect("Second line not found")
-        .trim_start_matches("Distance: ")
-        .split_ascii_whitespace()
-        .map(str::parse::<usize>)
-        .collect::<Result<Vec<_>, _>>()
-        .expect("expected successful parsing of distance");
+    let times = run(
+        labelled("Time", numbers()),
+        lines_it.next().expect("First line not found"),
+    )
+    .expect("expected successful parsing of time");
+    let distances = run(
+        labelled("Distance", numbers()),
+        lines_it.next().expect("Second line not found"),
+    )
+    .expect("expected successful parsing of distance");
 
     times.into_iter().zip(distances).map(Race::from).collect()
 }
@@ -77,7 +73,7 @@ fn parse_as_single_race(input: &str) -> Race {
 }
 
 fn main() {
-    let input = include_str!("input.txt");
+    let input = &aoc_common::puzzle_input!("input.txt");
     let races = parse_races(input);
     let part1_answer: usize = races
         .iter()
@@ -275,11 +275,12 @@ pub fn part2(input: &str) -> usize {
 
     use std::fs::OpenOptions;
     use std::io::Write;
+    // Kept out of the source tree, tests run from the crate directory
     let mut file = OpenOptions::new()
         .write(true)
         .truncate(true)
         .create(true)
-        .open("debug.csv")
+        .open(std::env::temp_dir().join("day_07_debug.csv"))
         .expect("expected to open the debug file");
 
     hands
@@ -2,6 +2,11 @@ use anyhow::Result;
 use pipe_maze::solve_parts;
 
 fn main() -> Result<()> {
-    solve_parts(include_str!("..\\input\\input.txt"))?;
+    #[cfg(windows)]
+    let input = &aoc_common::puzzle_input!("..\\input\\input.txt");
+    #[cfg(not(windows))]
+    let input = &aoc_common::puzzle_input!("../input/input.txt");
+
+    solve_parts(input)?;
     Ok(())
 }
@@ -1,13 +1,22 @@
 use std::collections::HashMap;
 
+use aoc_common::prelude::*;
+
+fn parse_pairs(input: &str) -> Vec<(i32, i32)> {
+    lines_with(input, |line| match unsigned::<i32>(line)?[..] {
+        [left, right] => Ok((left, right)),
+        _ => Err(ParseError::new(1, 1, ParseErrorKind::Expected("two numbers".to_owned()))),
+    })
+    .expect("expected two numbers per line")
+}
+
 pub fn part1(input: &str) {
     let mut left = Vec::with_capacity(1000);
     let mut right = Vec::with_capacity(1000);
 
-    input.lines().for_each(|line| {
-        let mut it = line.split_ascii_whitespace().take(2);
-        left.push(it.next().unwrap().parse::<i32>().unwrap());
-        right.push(it.next().unwrap().parse::<i32>().unwrap());
+    parse_pairs(input).into_iter().for_each(|(l, r)| {
+        left.push(l);
+        right.push(r);
     });
     left.sort();
     right.sort();
@@ -24,12 +33,9 @@ pub fn part2(input: &str) {
     let mut left = Vec::with_capacity(1000);
     let mut right = HashMap::new();
 
-    input.lines().for_each(|line| {
-        let mut it = line.split_ascii_whitespace().take(2);
-        left.push(it.next().unwrap().parse::<i32>().unwrap());
-        *right
-            .entry(it.next().unwrap().parse::<i32>().unwrap())
-            .or_insert(0) += 1;
+    parse_pairs(input).into_iter().for_each(|(l, r)| {
+        left.push(l);
+        *right.entry(r).or_insert(0) += 1;
     });
 
     let result = left
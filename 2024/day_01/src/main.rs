@@ -1,7 +1,7 @@
 use day_01::{part1, part2};
 
 fn main() {
-    let input = include_str!("../input.txt");
+    let input = &aoc_common::puzzle_input!("../input.txt");
     part1(input);
     part2(input);
 }
@@ -1,18 +1,12 @@
+use aoc_common::prelude::*;
+
 enum Direction {
     Increasing,
     Decreasing,
 }
 
 pub fn part1(input: &str) -> u32 {
-    let reports = input
-        .lines()
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .map(|n| n.parse())
-                .collect::<Result<Vec<u32>, _>>()
-                .unwrap()
-        })
-        .collect::<Vec<_>>();
+    let reports = lines_with(input, unsigned::<u32>).expect("expected numbers only");
 
     let mut count = 0;
 
@@ -57,15 +51,7 @@ fn is_report_valid(report: &[u32]) -> bool {
 }
 
 pub fn part2(input: &str) -> u32 {
-    let reports = input
-        .lines()
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .map(|n| n.parse())
-                .collect::<Result<Vec<u32>, _>>()
-                .unwrap()
-        })
-        .collect::<Vec<_>>();
+    let reports = lines_with(input, unsigned::<u32>).expect("expected numbers only");
 
     let mut count = 0;
 
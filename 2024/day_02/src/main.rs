@@ -1,7 +1,7 @@
 use day_02::{part1, part2};
 
 fn main() {
-    let input = include_str!("../input.txt");
+    let input = &aoc_common::puzzle_input!("../input.txt");
     println!("Part 1: - {}", part1(input));
     println!("Part 2: - {}", part2(input));
 }
@@ -1,3 +1,5 @@
+use aoc_common::prelude::*;
+
 type Joltage = u64;
 
 #[derive(Debug)]
@@ -48,15 +50,10 @@ impl Bank {
 }
 
 pub fn part1(input: &str) -> Joltage {
-    let banks = input
-        .lines()
-        .map(|line| {
-            Bank::new(
-                line.chars()
-                    .map(|c| c.to_digit(10).expect("expected a digit") as Joltage)
-                    .collect::<Vec<_>>(),
-            )
-        })
+    let banks = digit_grid(input)
+        .expect("expected a digit")
+        .into_iter()
+        .map(|row| Bank::new(row.into_iter().map(Joltage::from).collect()))
         .collect::<Vec<_>>();
 
     banks.iter().map(|b| b.get_max_joltage()).sum()
@@ -2,9 +2,9 @@ use day_04::{part1, part2};
 
 fn main() {
     #[cfg(windows)]
-    let input = include_str!("..\\input.txt");
+    let input = &aoc_common::puzzle_input!("..\\input.txt");
     #[cfg(not(windows))]
-    let input = include_str!("../input.txt");
+    let input = &aoc_common::puzzle_input!("../input.txt");
 
     println!("Part 1: - {}", part1(input));
     println!("Part 2: - {}", part2(input));
@@ -1,5 +1,7 @@
 use std::ops::RangeInclusive;
 
+use aoc_common::input::blocks;
+
 type Id = usize;
 
 #[derive(Debug, Default)]
@@ -78,10 +80,10 @@ pub fn part2(input: &str) -> usize {
 }
 
 fn parse_input(input: &str) -> Input {
-    let mut lines = input.lines();
+    let mut blocks = blocks(input);
     let mut result = Input::default();
     // Ranges
-    for line in lines.by_ref().take_while(|l| !l.is_empty()) {
+    for line in blocks.next().unwrap_or_default().lines() {
         let (l, r) = line
             .split_once('-')
             .expect("expected range with - in the middle");
@@ -91,7 +93,7 @@ fn parse_input(input: &str) -> Input {
         );
     }
     // Numbers
-    for line in lines.skip_while(|l| l.is_empty()) {
+    for line in blocks.next().unwrap_or_default().lines() {
         result
             .ids
             .push(line.parse::<Id>().expect("expected valid ID"));
@@ -82,7 +82,7 @@ pub fn part1(input: &str) -> usize {
 #[allow(clippy::needless_range_loop)]
 pub fn part2(input: &str) -> usize {
     let input = input
-        .split('\n')
+        .lines()
         .filter(|l| !l.is_empty())
         .map(|l| l.chars().collect())
         .collect::<Vec<Vec<char>>>();
@@ -10,26 +10,9 @@ pub fn part2(_input: &str) -> usize {
 mod tests {
     use super::*;
 
-    const TEST: &str = ".......S.......
-    ...............
-    .......^.......
-    ...............
-    ......^.^......
-    ...............
-    .....^.^.^.....
-    ...............
-    ....^.^...^....
-    ...............
-    ...^.^...^.^...
-    ...............
-    ..^...^.....^..
-    ...............
-    .^.^.^.^.^...^.
-    ...............";
-
-    #[test]
-    fn test_part1() {
-        assert_eq!(part1(TEST), 21);
+    aoc_macros::examples! {
+        part1: part1,
+        part2: part2,
     }
 
     #[test]
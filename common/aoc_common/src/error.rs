@@ -0,0 +1,88 @@
+//! Parse error with a position in the input.
+
+/// What went wrong, without the position
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    #[error("expected {0}")]
+    Expected(String),
+    #[error("invalid number `{0}`")]
+    InvalidNumber(String),
+    #[error("{0}")]
+    Invalid(String),
+    #[error("unexpected end of input")]
+    UnexpectedEnd,
+}
+
+/// Error returned by every parser of the crate.
+///
+/// `line` and `column` are 1-based, and the column counts characters rather
+/// than bytes, so it matches what an editor shows.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("line {line}, column {column}: {kind}")]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, kind: ParseErrorKind) -> Self {
+        Self { line, column, kind }
+    }
+
+    /// Error located at byte `offset` of `input`
+    pub fn at_offset(input: &str, offset: usize, kind: ParseErrorKind) -> Self {
+        let offset = offset.min(input.len());
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        let column = before[line_start..].chars().count() + 1;
+        Self::new(line, column, kind)
+    }
+
+    /// Error located where `rest` starts, `rest` being a suffix (or any
+    /// sub-slice) of `input`, as nom leaves it on failure
+    pub fn at(input: &str, rest: &str, kind: ParseErrorKind) -> Self {
+        Self::at_offset(input, offset_of(input, rest), kind)
+    }
+
+    /// Moves an error found while parsing `part` to its position in `input`.
+    ///
+    /// `part` must be a sub-slice of `input`, like a line or a block of it.
+    pub fn within(self, input: &str, part: &str) -> Self {
+        let origin = Self::at_offset(input, offset_of(input, part), self.kind.clone());
+        match self.line {
+            1 => Self::new(origin.line, origin.column + self.column - 1, self.kind),
+            line => Self::new(origin.line + line - 1, self.column, self.kind),
+        }
+    }
+}
+
+/// Byte offset of the sub-slice `part` in `input`, or the end of `input` when
+/// `part` doesn't point into it
+fn offset_of(input: &str, part: &str) -> usize {
+    let start = input.as_ptr() as usize;
+    let part = part.as_ptr() as usize;
+    if (start..=start + input.len()).contains(&part) {
+        part - start
+    } else {
+        input.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location() {
+        let input = "12\nab€d\nx";
+        let error = ParseError::at(input, &input[8..], ParseErrorKind::UnexpectedEnd);
+        assert_eq!((error.line, error.column), (2, 4));
+        assert_eq!(error.to_string(), "line 2, column 4: unexpected end of input");
+
+        let line = input.lines().nth(2).unwrap();
+        let error = ParseError::new(1, 1, ParseErrorKind::UnexpectedEnd).within(input, line);
+        assert_eq!((error.line, error.column), (3, 1));
+    }
+}
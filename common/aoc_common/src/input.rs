@@ -0,0 +1,270 @@
+//! Puzzle input preprocessing.
+//!
+//! Inputs come from files saved on different machines, so before any parsing
+//! the text goes through [`prepare`]:
+//!
+//! - a leading byte order mark is dropped;
+//! - `\r\n` and lone `\r` line endings become `\n`;
+//! - lines made only of whitespace become empty lines, so blank separators
+//!   are always `""`;
+//! - leading spaces are kept as is, as some puzzles (crate drawings, column
+//!   layouts) depend on them.
+//!
+//! Trailing whitespace is kept as well, but reported as a [`Warning`] unless
+//! it pads the lines of a block to the same width.
+//!
+//! Every day's binary loads its input with [`puzzle_input!`](crate::puzzle_input),
+//! which runs this step and reports the warnings, so the solutions only ever
+//! see `\n` line endings.
+
+use std::{fmt::Display, ops::Deref};
+
+const BOM: char = '\u{feff}';
+
+/// Something suspicious found while normalising the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// Line ends with spaces or tabs that don't look like layout
+    TrailingWhitespace { line: usize },
+    /// Line had only whitespace and was turned into an empty line
+    WhitespaceOnlyLine { line: usize },
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::TrailingWhitespace { line } => write!(f, "line {line}: trailing whitespace"),
+            Warning::WhitespaceOnlyLine { line } => {
+                write!(f, "line {line}: blank line contains whitespace")
+            }
+        }
+    }
+}
+
+/// Normalised puzzle input.
+///
+/// Dereferences to `str`, so it can be passed to the `part1`/`part2`
+/// functions as `&input`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    text: String,
+}
+
+impl Input {
+    /// Normalised `raw`, dropping the warnings [`prepare`] would give
+    pub fn new(raw: &str) -> Self {
+        prepare(raw).0
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Blocks of lines separated by blank lines, see [`blocks`]
+    pub fn blocks(&self) -> Blocks<'_> {
+        blocks(&self.text)
+    }
+}
+
+impl Deref for Input {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.text
+    }
+}
+
+impl AsRef<str> for Input {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Normalises the input, returning it along with what looked suspicious in it,
+/// in line order.
+///
+/// This is the step the binaries run on their `input.txt` before handing it to
+/// the solution, leaving it to them to report the warnings.
+pub fn prepare(raw: &str) -> (Input, Vec<Warning>) {
+    let raw = raw.strip_prefix(BOM).unwrap_or(raw);
+    let raw = raw.replace("\r\n", "\n").replace('\r', "\n");
+
+    let mut text = String::with_capacity(raw.len());
+    let mut warnings = Vec::new();
+    for (idx, line) in raw.split('\n').enumerate() {
+        if idx > 0 {
+            text.push('\n');
+        }
+        if !line.is_empty() && line.trim().is_empty() {
+            warnings.push(Warning::WhitespaceOnlyLine { line: idx + 1 });
+        } else {
+            text.push_str(line);
+        }
+    }
+    warnings.extend(find_trailing_whitespace(&text));
+    warnings.sort_by_key(|w| match w {
+        Warning::TrailingWhitespace { line } | Warning::WhitespaceOnlyLine { line } => *line,
+    });
+
+    (Input { text }, warnings)
+}
+
+/// Puzzle input of the calling crate: the file at `path` is included like
+/// with `include_str!`, run through [`prepare`] and its warnings are printed
+/// on stderr.
+///
+/// ```ignore
+/// let input = aoc_common::puzzle_input!("../input.txt");
+/// println!("{}", part1(&input));
+/// ```
+#[macro_export]
+macro_rules! puzzle_input {
+    ($path:literal) => {{
+        let (input, warnings) = $crate::input::prepare(include_str!($path));
+        for warning in &warnings {
+            eprintln!("input warning: {warning}");
+        }
+        input
+    }};
+}
+
+/// Splits `text` into blocks of lines separated by one or more blank lines.
+///
+/// Blank lines at the start and the end of the text are ignored, and the
+/// returned blocks don't include their last line ending. Lines holding only
+/// whitespace count as blank, so this works on text that wasn't normalised
+/// as long as it uses `\n` line endings.
+pub fn blocks(text: &str) -> Blocks<'_> {
+    Blocks { rest: text }
+}
+
+/// Iterator returned by [`blocks`]
+#[derive(Debug, Clone)]
+pub struct Blocks<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip the separator lines in front of the block
+        loop {
+            if self.rest.is_empty() {
+                return None;
+            }
+            let (line, rest) = split_line(self.rest);
+            if !line.trim().is_empty() {
+                break;
+            }
+            self.rest = rest;
+        }
+
+        let text = self.rest;
+        let mut end = 0;
+        while end < text.len() {
+            let (line, _) = split_line(&text[end..]);
+            if line.trim().is_empty() {
+                break;
+            }
+            end += line.len();
+            if end < text.len() {
+                // Step over the '\n'
+                end += 1;
+            }
+        }
+        self.rest = &text[end..];
+        Some(text[..end].trim_end_matches(['\n', '\r']))
+    }
+}
+
+/// Returns the first line (without its '\n') and the text after it
+fn split_line(text: &str) -> (&str, &str) {
+    match text.split_once('\n') {
+        Some((line, rest)) => (line, rest),
+        None => (text, ""),
+    }
+}
+
+/// Trailing whitespace is fine when it pads every line of a block to the same
+/// width, as in column based puzzles. Anywhere else it is reported.
+fn find_trailing_whitespace(text: &str) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let mut block: Vec<(usize, &str)> = Vec::new();
+
+    let mut flush = |block: &mut Vec<(usize, &str)>| {
+        let width = block.first().map(|(_, line)| line.chars().count());
+        let rectangular = block
+            .iter()
+            .all(|(_, line)| Some(line.chars().count()) == width);
+        if !rectangular || block.len() == 1 {
+            warnings.extend(
+                block
+                    .iter()
+                    .filter(|(_, line)| line.ends_with([' ', '\t']))
+                    .map(|(idx, _)| Warning::TrailingWhitespace { line: idx + 1 }),
+            );
+        }
+        block.clear();
+    };
+
+    for (idx, line) in text.split('\n').enumerate() {
+        if line.is_empty() {
+            flush(&mut block);
+        } else {
+            block.push((idx, line));
+        }
+    }
+    flush(&mut block);
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_endings_and_bom() {
+        let (input, warnings) = prepare("\u{feff}1\r\n2\r\n\r\n3\r4\n");
+        assert_eq!(input.as_str(), "1\n2\n\n3\n4\n");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn leading_spaces_are_kept() {
+        let drawing = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n";
+        let (input, warnings) = prepare(drawing);
+        assert_eq!(input.as_str(), drawing);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn trailing_whitespace_warnings() {
+        let (input, warnings) = prepare("1-3 \n5-7\n  \n1\n");
+        assert_eq!(input, Input::new("1-3 \n5-7\n  \n1\n"));
+        assert_eq!(input.as_str(), "1-3 \n5-7\n\n1\n");
+        assert_eq!(
+            warnings,
+            [
+                Warning::TrailingWhitespace { line: 1 },
+                Warning::WhitespaceOnlyLine { line: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn blocks_skip_blank_runs() {
+        let text = "\n1000\n2000\n\n\n\n4000\n \n5000\n6000\n\n";
+        assert_eq!(blocks(text).collect::<Vec<_>>(), ["1000\n2000", "4000", "5000\n6000"]);
+        assert_eq!(blocks("").count(), 0);
+        assert_eq!(blocks("\n\n").count(), 0);
+        assert_eq!(blocks("7").collect::<Vec<_>>(), ["7"]);
+    }
+
+    #[test]
+    fn blocks_after_crlf() {
+        let input = Input::new("1\r\n2\r\n\r\n3\r\n");
+        assert_eq!(input.blocks().collect::<Vec<_>>(), ["1\n2", "3"]);
+    }
+}
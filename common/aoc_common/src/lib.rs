@@ -0,0 +1,18 @@
+//! Helpers shared by the day crates of every year.
+
+pub mod error;
+pub mod input;
+pub mod parse;
+
+/// Everything a day crate usually needs to read its input
+pub mod prelude {
+    pub use crate::{
+        error::{ParseError, ParseErrorKind},
+        input::{blocks, prepare, Input},
+        puzzle_input,
+        parse::{
+            combinator::{self, run},
+            digit_grid, lines_as, lines_with, signed, split_pair, unsigned,
+        },
+    };
+}
@@ -0,0 +1,137 @@
+//! Parsers for the patterns most puzzle inputs are made of.
+//!
+//! Every function reports failures as a [`ParseError`] pointing at the line
+//! and column of the offending text.
+
+use std::{fmt::Display, str::FromStr};
+
+pub use crate::input::blocks;
+use crate::error::{ParseError, ParseErrorKind};
+
+pub mod combinator;
+
+/// Extracts every run of ASCII digits of `text`, ignoring anything between
+/// them.
+///
+/// A `-` in front of a number is not a sign here, so `"1-3"` gives `[1, 3]`.
+pub fn unsigned<T: FromStr>(text: &str) -> Result<Vec<T>, ParseError> {
+    numbers(text, false)
+}
+
+/// Like [`unsigned`], but a `-` or `+` directly in front of the digits is part
+/// of the number, so `"x=-3, y=+4"` gives `[-3, 4]`.
+pub fn signed<T: FromStr>(text: &str) -> Result<Vec<T>, ParseError> {
+    numbers(text, true)
+}
+
+fn numbers<T: FromStr>(text: &str, signed: bool) -> Result<Vec<T>, ParseError> {
+    let bytes = text.as_bytes();
+    let mut result = Vec::new();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if !bytes[idx].is_ascii_digit() {
+            idx += 1;
+            continue;
+        }
+        let mut start = idx;
+        if signed && start > 0 && matches!(bytes[start - 1], b'-' | b'+') {
+            start -= 1;
+        }
+        while idx < bytes.len() && bytes[idx].is_ascii_digit() {
+            idx += 1;
+        }
+        let number = &text[start..idx];
+        let value = number.parse().map_err(|_| {
+            ParseError::at_offset(text, start, ParseErrorKind::InvalidNumber(number.to_owned()))
+        })?;
+        result.push(value);
+    }
+    Ok(result)
+}
+
+/// Parses every line of `text` with `FromStr`
+pub fn lines_as<T>(text: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    lines_with(text, |line| {
+        line.parse()
+            .map_err(|e: T::Err| ParseError::new(1, 1, ParseErrorKind::Invalid(e.to_string())))
+    })
+}
+
+/// Parses every line of `text` with `parser`.
+///
+/// Errors of `parser` are relative to the line it was given and get moved to
+/// their position in `text`.
+pub fn lines_with<T, F>(text: &str, mut parser: F) -> Result<Vec<T>, ParseError>
+where
+    F: FnMut(&str) -> Result<T, ParseError>,
+{
+    text.lines()
+        .map(|line| parser(line).map_err(|e| e.within(text, line)))
+        .collect()
+}
+
+/// Splits `line` around the first `separator`, erroring when there is none
+pub fn split_pair(line: &str, separator: char) -> Result<(&str, &str), ParseError> {
+    line.split_once(separator).ok_or_else(|| {
+        ParseError::at_offset(line, line.len(), ParseErrorKind::Expected(format!("`{separator}`")))
+    })
+}
+
+/// Reads a grid of single digits, one row per line
+pub fn digit_grid(text: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    lines_with(text, |line| {
+        line.chars()
+            .enumerate()
+            .map(|(column, c)| {
+                c.to_digit(10).map(|d| d as u8).ok_or_else(|| {
+                    ParseError::new(1, column + 1, ParseErrorKind::Expected("a digit".to_owned()))
+                })
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number_extraction() {
+        assert_eq!(unsigned::<u32>("Time:      7  15   30").unwrap(), [7, 15, 30]);
+        assert_eq!(unsigned::<u32>("2-4,6-8").unwrap(), [2, 4, 6, 8]);
+        assert_eq!(signed::<i32>("x=-3, y=+4 z=5").unwrap(), [-3, 4, 5]);
+        assert!(unsigned::<u8>("").unwrap().is_empty());
+
+        let error = unsigned::<u8>("1 2\n3 256").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(error.kind, ParseErrorKind::InvalidNumber("256".to_owned()));
+    }
+
+    #[test]
+    fn typed_lines() {
+        assert_eq!(lines_as::<i64>("1\n-2\n3\n").unwrap(), [1, -2, 3]);
+        let error = lines_as::<i64>("1\nx\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+
+        let rows = lines_with("7 6 4\n1 2", unsigned::<u32>).unwrap();
+        assert_eq!(rows, [vec![7, 6, 4], vec![1, 2]]);
+    }
+
+    #[test]
+    fn pair() {
+        assert_eq!(split_pair("Card 1: 41 48", ':').unwrap(), ("Card 1", " 41 48"));
+        let error = split_pair("Card 1", ':').unwrap_err();
+        assert_eq!((error.line, error.column), (1, 7));
+    }
+
+    #[test]
+    fn digits() {
+        assert_eq!(digit_grid("123\n450\n").unwrap(), [vec![1, 2, 3], vec![4, 5, 0]]);
+        let error = digit_grid("12\n3x").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+}
@@ -0,0 +1,91 @@
+//! A few nom combinators for the usual puzzle line shapes, and [`run`] to turn
+//! their result into a [`ParseError`].
+
+use std::str::FromStr;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{char, digit1, line_ending, space0, space1},
+    combinator::{all_consuming, map_res, opt, recognize},
+    error::Error,
+    multi::separated_list1,
+    sequence::{pair, preceded, terminated, tuple},
+    Finish, IResult, Parser,
+};
+
+use crate::error::{ParseError, ParseErrorKind};
+
+/// Unsigned integer
+pub fn unsigned<'a, T: FromStr>() -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    map_res(digit1, str::parse)
+}
+
+/// Integer with an optional `-` or `+` sign
+pub fn signed<'a, T: FromStr>() -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    map_res(recognize(pair(opt(nom::character::complete::one_of("+-")), digit1)), str::parse)
+}
+
+/// Space separated list of unsigned integers, leading spaces allowed
+pub fn numbers<'a, T: FromStr>() -> impl FnMut(&'a str) -> IResult<&'a str, Vec<T>> {
+    preceded(space0, separated_list1(space1, unsigned()))
+}
+
+/// `label: value`, with any number of spaces after the colon, as in
+/// `Time:      7  15   30`
+pub fn labelled<'a, O, P>(label: &'a str, value: P) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    P: Parser<&'a str, O, Error<&'a str>>,
+{
+    preceded(tuple((tag(label), char(':'), space0)), value)
+}
+
+/// One `item` per line, the last line ending being optional
+pub fn lines_of<'a, O, P>(item: P) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    P: Parser<&'a str, O, Error<&'a str>>,
+{
+    terminated(separated_list1(line_ending, item), opt(line_ending))
+}
+
+/// Runs `parser` over the whole of `input`.
+///
+/// Leftover input is an error, located where the parser stopped.
+pub fn run<'a, O, P>(parser: P, input: &'a str) -> Result<O, ParseError>
+where
+    P: Parser<&'a str, O, Error<&'a str>>,
+{
+    all_consuming(parser)(input)
+        .finish()
+        .map(|(_, output)| output)
+        .map_err(|e| {
+            let kind = match e.input.is_empty() {
+                true => ParseErrorKind::UnexpectedEnd,
+                false => ParseErrorKind::Expected(e.code.description().to_lowercase()),
+            };
+            ParseError::at(input, e.input, kind)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labelled_numbers() {
+        let times = run(labelled("Time", numbers::<u32>()), "Time:      7  15   30").unwrap();
+        assert_eq!(times, [7, 15, 30]);
+
+        let error = run(labelled("Time", numbers::<u32>()), "Distance: 9").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+
+    #[test]
+    fn lines() {
+        let parser = lines_of(separated_list1(char(','), signed::<i32>()));
+        assert_eq!(run(parser, "1,-2\n+3\n").unwrap(), [vec![1, -2], vec![3]]);
+
+        let parser = lines_of(separated_list1(char(','), signed::<i32>()));
+        let error = run(parser, "1,2\n3;4\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+}
@@ -0,0 +1,152 @@
+//! Procedural macros shared by the day crates.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Expr, Ident, Token,
+};
+
+/// Directory, relative to the crate manifest, that holds the examples
+const EXAMPLES_DIR: &str = "examples";
+
+/// One `label: solver` entry of the macro input
+struct Part {
+    label: Ident,
+    solver: Expr,
+}
+
+impl Parse for Part {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let label = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let solver = input.parse()?;
+        Ok(Self { label, solver })
+    }
+}
+
+/// An example input found on disk
+struct Example {
+    stem: String,
+    input: PathBuf,
+}
+
+/// Generates one test per example file and part.
+///
+/// Every `examples/<name>.txt` file of the calling crate is an example input,
+/// stored exactly as the puzzle gives it. The expected answer for a part lives
+/// next to it in `examples/<name>.<label>`, so an example can cover only one of
+/// the parts. The solver is anything callable as `fn(&str) -> impl Display`,
+/// and its output is compared to the answer file with trailing whitespace
+/// ignored.
+///
+/// ```ignore
+/// #[cfg(test)]
+/// mod tests {
+///     use super::*;
+///
+///     aoc_macros::examples! {
+///         part1: part1,
+///         part2: |input| calculate_points(build_strategy_map(input)),
+///     }
+/// }
+/// ```
+///
+/// With `examples/sample.txt` and `examples/sample.part1` this expands to a
+/// `part1_sample` test. Adding a new file needs a rebuild of the crate to be
+/// picked up, as the directory is only read when the macro expands.
+#[proc_macro]
+pub fn examples(input: TokenStream) -> TokenStream {
+    let parts = parse_macro_input!(input with Punctuated::<Part, Token![,]>::parse_terminated);
+
+    match expand(&parts) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(parts: &Punctuated<Part, Token![,]>) -> syn::Result<proc_macro2::TokenStream> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| error("CARGO_MANIFEST_DIR is not set"))?;
+    let dir = Path::new(&manifest_dir).join(EXAMPLES_DIR);
+    let examples = find_examples(&dir)?;
+
+    let mut tests = Vec::new();
+    for example in &examples {
+        let mut answered = false;
+        for Part { label, solver } in parts {
+            let answer = dir.join(format!("{}.{label}", example.stem));
+            if !answer.is_file() {
+                continue;
+            }
+            answered = true;
+
+            let name = format_ident!("{label}_{}", sanitize(&example.stem));
+            let input = path_str(&example.input)?;
+            let answer = path_str(&answer)?;
+            let file = format!("{}.txt", example.stem);
+            tests.push(quote! {
+                #[test]
+                fn #name() {
+                    let input: &str = include_str!(#input);
+                    let expected: &str = include_str!(#answer);
+                    let solve: fn(&str) -> _ = #solver;
+                    let actual = ::std::string::ToString::to_string(&solve(input));
+                    assert_eq!(actual.trim_end(), expected.trim_end(), "example `{}`", #file);
+                }
+            });
+        }
+        if !answered {
+            return Err(error(&format!(
+                "example `{}.txt` has no expected answer for any of the given parts",
+                example.stem
+            )));
+        }
+    }
+
+    Ok(quote! { #(#tests)* })
+}
+
+/// Lists `*.txt` files of the examples directory, sorted by name
+fn find_examples(dir: &Path) -> syn::Result<Vec<Example>> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| error(&format!("can't read examples in {}: {e}", dir.display())))?;
+
+    let mut examples = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+        .filter_map(|input| {
+            let stem = input.file_stem()?.to_str()?.to_owned();
+            Some(Example { stem, input })
+        })
+        .collect::<Vec<_>>();
+    if examples.is_empty() {
+        return Err(error(&format!("no `*.txt` examples in {}", dir.display())));
+    }
+    examples.sort_by(|a, b| a.stem.cmp(&b.stem));
+    Ok(examples)
+}
+
+/// Turns a file stem into something usable as part of an identifier
+fn sanitize(stem: &str) -> String {
+    stem.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect()
+}
+
+fn path_str(path: &Path) -> syn::Result<&str> {
+    path.to_str()
+        .ok_or_else(|| error(&format!("non UTF-8 path: {}", path.display())))
+}
+
+fn error(message: &str) -> syn::Error {
+    syn::Error::new(Span::call_site(), message)
+}